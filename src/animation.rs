@@ -0,0 +1,221 @@
+use crate::{cgol::CgolCell, grid::Grid, render::Renderer};
+use std::{
+    cell::Cell,
+    convert::TryFrom,
+    io::{self, Write},
+    rc::Rc,
+    time::Duration,
+};
+
+/// Records consecutive states of a grid as frames of an animated GIF, which
+/// loops forever
+///
+/// The GIF has to be finished with `finish` to find out whether it was written
+/// completely. Dropping the recorder also finishes it, but ignores any errors.
+pub struct GifRecorder<W: Write> {
+    writer: Option<W>,
+    encoder: Option<gif::Encoder<Checked<W>>>,
+    /// First error of the writer, including the ones from writing the end of the
+    /// GIF when the encoder is dropped
+    error: Rc<Cell<Option<io::Error>>>,
+    renderer: Renderer,
+    region: Option<[usize; 4]>,
+    delay: Duration,
+}
+
+impl<W: Write> GifRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            encoder: None,
+            error: Rc::default(),
+            renderer: Renderer::default(),
+            region: None,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    /// Sets the renderer used to draw frames, whose cell size scales the frames
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Records only the `[left, top, cols, rows]` region of the grid instead of
+    /// the whole grid
+    pub fn region(mut self, region: [usize; 4]) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Sets the time each frame is shown for, which GIFs store in hundredths of
+    /// a second
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Adds the grid as the next frame
+    ///
+    /// The size of the animation is set by the first frame, later frames are
+    /// cropped or padded with dead cells to it.
+    pub fn record(&mut self, grid: &Grid<CgolCell>) -> io::Result<()> {
+        let [left, top, cols, rows] = *self.region.get_or_insert([0, 0, grid.cols(), grid.rows()]);
+        let image = self.renderer.render(&grid.crop(left, top, cols, rows));
+
+        let size = |n: usize| {
+            u16::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))
+        };
+        let (width, height) = (size(image.width())?, size(image.height())?);
+
+        // The encoder is created with the first frame once its size is known
+        if let Some(writer) = self.writer.take() {
+            let writer = Checked { writer, error: self.error.clone() };
+            let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(io_error)?;
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(io_error)?;
+            self.encoder = Some(encoder);
+        }
+        let encoder = self
+            .encoder
+            .as_mut()
+            .ok_or_else(|| io::Error::other("failed to start recording"))?;
+
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut image.as_bytes().to_vec(), 10);
+        frame.delay = (self.delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        encoder.write_frame(&frame).map_err(io_error)?;
+        Ok(())
+    }
+
+    /// Writes the end of the GIF & flushes the writer
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            return writer.flush();
+        }
+
+        drop(self.encoder.take());
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writer keeping a copy of its first error, so that errors from writing the
+/// end of the GIF while the encoder is dropped aren't lost, which also flushes
+/// when dropped itself
+struct Checked<W: Write> {
+    writer: W,
+    error: Rc<Cell<Option<io::Error>>>,
+}
+
+impl<W: Write> Checked<W> {
+    fn check<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        if let Err(e) = &result {
+            let first = self.error.take().unwrap_or_else(|| io::Error::new(e.kind(), e.to_string()));
+            self.error.set(Some(first));
+        }
+        result
+    }
+}
+
+impl<W: Write> Write for Checked<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.writer.write(buf);
+        self.check(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.writer.flush();
+        self.check(result)
+    }
+}
+
+impl<W: Write> Drop for Checked<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+fn io_error(e: gif::EncodingError) -> io::Error {
+    match e {
+        gif::EncodingError::Io(e) => e,
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{cgol::patterns, Automaton, Cgol};
+
+    #[test]
+    fn glider() {
+        let mut data = Vec::new();
+        let mut cgol = Automaton::<Cgol>::new([8, 8]);
+        cgol.put(&patterns::GLIDER, 0, 0);
+
+        let renderer = Renderer { cell_size: 2.0, show_age: false, ..Renderer::default() };
+        let mut recorder = GifRecorder::new(&mut data)
+            .renderer(renderer)
+            .region([0, 0, 6, 5])
+            .delay(Duration::from_millis(50));
+
+        for _ in 0..4 {
+            recorder.record(cgol.cells()).unwrap();
+            cgol.step();
+        }
+        recorder.finish().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(&data[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (12, 10));
+
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            // Top cell of the glider, which is only live in the first phase
+            let live = &frame.buffer[2 * 4..][..4];
+            assert_eq!(live == [255, 255, 255, 255], frames == 0);
+            frames += 1;
+        }
+        assert_eq!(frames, 4);
+    }
+
+    #[test]
+    fn resized() {
+        let mut data = Vec::new();
+        let renderer = Renderer { cell_size: 1.0, ..Renderer::default() };
+        let mut recorder = GifRecorder::new(&mut data).renderer(renderer);
+
+        // Frames after the first are cropped or padded to its size
+        for &(cols, rows) in &[(4, 3), (6, 2), (2, 5)] {
+            recorder.record(&Grid::new(cols, rows)).unwrap();
+        }
+        recorder.finish().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(&data[..]).unwrap();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height), (4, 3));
+        }
+    }
+
+    #[test]
+    fn write_error() {
+        struct Full;
+
+        impl Write for Full {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::WriteZero, "disk full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> { Ok(()) }
+        }
+
+        let mut recorder = GifRecorder::new(Full);
+        assert!(recorder.record(&patterns::GLIDER).is_err());
+        assert_eq!(recorder.finish().unwrap_err().to_string(), "disk full");
+    }
+}
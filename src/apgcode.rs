@@ -0,0 +1,226 @@
+//! Encoding of periodic patterns as apgcodes, the identifiers used by apgsearch
+//! and Catagolue, e.g. `xs4_33` for the block or `xq4_153` for the glider
+//!
+//! The body of an apgcode is the pattern in extended Wechsler format: the pattern
+//! is cut into strips 5 cells tall, each column of a strip is written as a base 32
+//! digit with the top cell in the lowest bit, runs of empty columns are shortened
+//! to `w`, `x` or `y` and the strips are separated by `z`.
+
+use crate::{
+    automaton::Automaton,
+    cgol::{Cgol, CgolCell},
+    grid::Grid,
+    period::{find_period, live_cells},
+};
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Returns the apgcode of the pattern, canonicalized over all its phases,
+/// rotations and reflections
+///
+/// Returns `None` if the pattern isn't a still life, an oscillator or
+/// a spaceship with a period of at most `max_period`.
+pub fn encode(pattern: &Grid<CgolCell>, max_period: usize) -> Option<String> {
+    let period = find_period(pattern, max_period)?;
+
+    let pad = period.period + 1;
+    let mut cgol = Automaton::<Cgol>::new([pattern.cols() + 2 * pad, pattern.rows() + 2 * pad]);
+    cgol.put(pattern, pad as isize, pad as isize);
+
+    let mut best: Option<String> = None;
+    for _ in 0..period.period {
+        for phase in crop(cgol.cells()).symmetries() {
+            let body = wechsler(&phase);
+
+            // The shortest representation wins, ties are broken lexicographically
+            if best.as_ref().is_none_or(|b| (body.len(), &body) < (b.len(), b)) {
+                best = Some(body);
+            }
+        }
+
+        cgol.step();
+    }
+
+    let body = best?;
+    Some(if period.is_still() {
+        format!("xs{}_{}", live_cells(pattern).0.len(), body)
+    } else if period.is_moving() {
+        format!("xq{}_{}", period.period, body)
+    } else {
+        format!("xp{}_{}", period.period, body)
+    })
+}
+
+/// Returns the pattern described by the apgcode, cropped to its live cells
+pub fn decode(code: &str) -> Result<Grid<CgolCell>, String> {
+    let body = match code.split_once('_') {
+        Some((prefix, body))
+            if ["xs", "xp", "xq"].iter().any(|p| {
+                prefix.strip_prefix(p).is_some_and(|n| n.parse::<usize>().is_ok())
+            }) => body,
+        _ => return Err(format!("not a still life, oscillator or spaceship apgcode: {}", code)),
+    };
+
+    let mut live = Vec::new();
+    let (mut col, mut strip) = (0, 0);
+    let mut chars = body.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => col += 2,
+            'x' => col += 3,
+            'y' => {
+                let n = chars.next().and_then(digit).ok_or_else(|| format!("invalid apgcode: {}", code))?;
+                col += 4 + n;
+            }
+            'z' => {
+                col = 0;
+                strip += 1;
+            }
+            _ => {
+                let bits = digit(c).filter(|&n| n < 32).ok_or_else(|| format!("invalid apgcode: {}", code))?;
+                for bit in 0..5 {
+                    if bits & 1 << bit != 0 {
+                        live.push((col, strip * 5 + bit));
+                    }
+                }
+                col += 1;
+            }
+        }
+    }
+
+    if live.is_empty() {
+        return Err(format!("empty apgcode: {}", code));
+    }
+
+    let left = live.iter().map(|&(col, _)| col).min().unwrap();
+    let top = live.iter().map(|&(_, row)| row).min().unwrap();
+    let right = live.iter().map(|&(col, _)| col).max().unwrap();
+    let bottom = live.iter().map(|&(_, row)| row).max().unwrap();
+
+    let mut pattern = Grid::new(right - left + 1, bottom - top + 1);
+    for (col, row) in live {
+        pattern[(col - left, row - top)] = CgolCell::Live(0);
+    }
+    Ok(pattern)
+}
+
+fn digit(c: char) -> Option<usize> {
+    DIGITS.iter().position(|&d| d as char == c)
+}
+
+/// Returns the live cells of the grid cropped to their bounding box
+fn crop(grid: &Grid<CgolCell>) -> Grid<bool> {
+    let (shape, _) = live_cells(grid);
+    let cols = shape.iter().map(|&(col, _)| col + 1).max().unwrap_or(0);
+    let rows = shape.iter().map(|&(_, row)| row + 1).max().unwrap_or(0);
+
+    let mut cropped = Grid::new(cols, rows);
+    for pos in shape {
+        cropped[pos] = true;
+    }
+    cropped
+}
+
+/// Encodes a single orientation of a pattern in extended Wechsler format
+fn wechsler(pattern: &Grid<bool>) -> String {
+    let mut strips = Vec::new();
+
+    for top in (0..pattern.rows()).step_by(5) {
+        let mut columns = (0..pattern.cols())
+            .map(|col| {
+                (top..(top + 5).min(pattern.rows()))
+                    .filter(|&row| pattern[(col, row)])
+                    .fold(0, |bits, row| bits | 1 << (row - top))
+            })
+            .collect::<Vec<usize>>();
+
+        while columns.last() == Some(&0) {
+            columns.pop();
+        }
+
+        let mut strip = String::new();
+        let mut zeros = 0;
+        for bits in columns {
+            if bits == 0 {
+                zeros += 1;
+                continue;
+            }
+
+            while zeros > 0 {
+                let n = zeros.min(39);
+                match n {
+                    1 => strip.push('0'),
+                    2 => strip.push('w'),
+                    3 => strip.push('x'),
+                    _ => {
+                        strip.push('y');
+                        strip.push(DIGITS[n - 4] as char);
+                    }
+                }
+                zeros -= n;
+            }
+
+            strip.push(DIGITS[bits] as char);
+        }
+
+        strips.push(strip);
+    }
+
+    strips.join("z")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns::{self, apgcodes};
+
+    #[test]
+    fn encode_patterns() {
+        for &(pattern, code) in &[
+            (&*patterns::BLOCK_2, apgcodes::BLOCK_2),
+            (&*patterns::BEEHIVE, apgcodes::BEEHIVE),
+            (&*patterns::LOAF, apgcodes::LOAF),
+            (&*patterns::BOAT, apgcodes::BOAT),
+            (&*patterns::TUB, apgcodes::TUB),
+            (&*patterns::BLINKER, apgcodes::BLINKER),
+            (&*patterns::TOAD, apgcodes::TOAD),
+            (&*patterns::BEACON, apgcodes::BEACON),
+            (&*patterns::PULSAR, apgcodes::PULSAR),
+            (&*patterns::GLIDER, apgcodes::GLIDER),
+            (&*patterns::LWSS, apgcodes::LWSS),
+            (&*patterns::MWSS, apgcodes::MWSS),
+            (&*patterns::HWSS, apgcodes::HWSS),
+        ] {
+            assert_eq!(encode(pattern, 8).as_deref(), Some(code));
+        }
+    }
+
+    #[test]
+    fn canonical() {
+        for phase in patterns::GLIDER.symmetries() {
+            assert_eq!(encode(&phase, 8).as_deref(), Some("xq4_153"));
+        }
+        assert_eq!(encode(&patterns::BLOCK_1, 8), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        for code in &[apgcodes::PULSAR, apgcodes::MWSS, "xp15_4r4z4r4", "xs8_33w33"] {
+            let pattern = decode(code).unwrap();
+            assert_eq!(encode(&pattern, 15).as_deref(), Some(*code));
+        }
+
+        let blocks = decode("xs8_33y233").unwrap();
+        assert_eq!((blocks.cols(), blocks.rows()), (10, 2));
+        assert_eq!(blocks[(8, 0)], CgolCell::Live(0));
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert!(decode("xs4").is_err());
+        assert!(decode("yl144_1_16_afb5f3db909e60548f086e22ee3353ac").is_err());
+        assert!(decode("xs4_3!").is_err());
+        assert!(decode("xs0_").is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::grid::Grid;
+use crate::grid::{Change, Grid};
+use std::{collections::VecDeque, str::FromStr};
 
 pub type UpdateNeighbor<R> = Option<fn(&mut <R as Ruleset>::NeighborData)>;
 
@@ -14,12 +15,165 @@ pub trait Ruleset {
     /// Returns a function that will be called for each of the cell's neighbors
     /// based on the previous and current state of the cell
     fn update_neighbor(prev: &Self::State, curr: &Self::State) -> UpdateNeighbor<Self>;
+
+    /// Returns whether the cell counts towards the population
+    ///
+    /// There is no default as `State` isn't required to tell its dead state
+    /// apart, so this is a breaking change for rulesets written before 0.2,
+    /// which need to implement it.
+    fn is_live(s: &Self::State) -> bool;
+
+    /// Returns the age of a live cell if the ruleset keeps track of it
+    fn age(_s: &Self::State) -> Option<u64> { None }
+}
+
+/// Statistics about live cells, kept up to date as the automaton changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of live cells
+    pub population: usize,
+    /// Number of cells that came alive in the last step
+    pub births: usize,
+    /// Number of cells that died in the last step
+    pub deaths: usize,
+    /// Sum of the ages of all live cells, if the ruleset keeps track of ages
+    pub total_age: u64,
 }
 
+impl Stats {
+    /// Returns the average age of live cells
+    pub fn mean_age(&self) -> Option<f64> {
+        if self.population > 0 {
+            Some(self.total_age as f64 / self.population as f64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Receives events from an automaton it has been added to
+///
+/// All methods do nothing by default so observers only implement the events they
+/// are interested in.
+pub trait Observer<R: Ruleset> {
+    /// Called after each step with the automaton in its new generation
+    fn on_step(&mut self, _automaton: &Automaton<R>) {}
+
+    /// Called when a cell becomes live or dead, either by a step or by an edit
+    ///
+    /// Clearing the board and resizing it past live cells count as edits that
+    /// kill those cells.
+    fn on_cell_changed(&mut self, _col: usize, _row: usize, _prev: &R::State, _curr: &R::State) {}
+
+    /// Called after the board is resized, with the offset by which the remaining
+    /// cells moved
+    ///
+    /// Moved cells aren't reported by `on_cell_changed`, so positions recorded
+    /// before have to be shifted by the offset.
+    fn on_resized(&mut self, _automaton: &Automaton<R>, _offset: [isize; 2]) {}
+
+    /// Called after the step in which the last live cell died
+    fn on_population_zero(&mut self, _automaton: &Automaton<R>) {}
+
+    /// Called once when the live cells repeat those of `period` generations ago
+    ///
+    /// Only the positions of live cells are compared, so oscillators & still lifes
+    /// are detected but moving patterns are not. Periods up to `MAX_CYCLE_PERIOD`
+    /// are detected.
+    fn on_cycle_detected(&mut self, _automaton: &Automaton<R>, _period: usize) {}
+}
+
+/// State of an automaton from which it can be fully restored
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot<S> {
+    pub generation: u64,
+    pub cells: Grid<S>,
+}
+
+/// Point of the board that stays in place when it's resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Returns how far cells move when a board of the old size is resized to the
+    /// new one
+    pub fn offset(self, old: [usize; 2], new: [usize; 2]) -> [isize; 2] {
+        use Anchor::*;
+
+        // Whether the anchor is at the start, middle or end of each axis
+        let (x, y) = match self {
+            TopLeft => (0, 0),
+            Top => (1, 0),
+            TopRight => (2, 0),
+            Left => (0, 1),
+            Center => (1, 1),
+            Right => (2, 1),
+            BottomLeft => (0, 2),
+            Bottom => (1, 2),
+            BottomRight => (2, 2),
+        };
+        let offset = |position: isize, old: usize, new: usize| (new as isize - old as isize) * position / 2;
+
+        [offset(x, old[0], new[0]), offset(y, old[1], new[1])]
+    }
+}
+
+impl FromStr for Anchor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Self::TopLeft),
+            "top" => Ok(Self::Top),
+            "top-right" => Ok(Self::TopRight),
+            "left" => Ok(Self::Left),
+            "center" => Ok(Self::Center),
+            "right" => Ok(Self::Right),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom" => Ok(Self::Bottom),
+            "bottom-right" => Ok(Self::BottomRight),
+            _ => Err(format!("unknown anchor: {}", s)),
+        }
+    }
+}
+
+/// Longest period reported to `Observer::on_cycle_detected`
+pub const MAX_CYCLE_PERIOD: usize = 64;
+
 /// Stores cell state & runs rules
 pub struct Automaton<R: Ruleset> {
     cells: [Grid<R::State>; 2],
     neighbor_data: [Grid<R::NeighborData>; 2],
+    generation: u64,
+    stats: Stats,
+    /// Number of live cells in each column & row, used to find the bounds of live
+    /// cells without scanning the whole grid
+    col_population: Vec<usize>,
+    row_population: Vec<usize>,
+    /// Hash of the positions of live cells, updated with each birth & death
+    ///
+    /// It's 128 bits wide so that two different sets of live cells practically
+    /// never get the same hash and report a false cycle.
+    live_hash: u128,
+    /// Population & live hash of the generations before the current one since
+    /// the last edit, used to detect cycles
+    history: VecDeque<(usize, u128)>,
+    /// Period of the cycle the automaton is in, if one has been detected
+    cycle: Option<usize>,
+    /// Cells that became live or dead in the last step, if tracked
+    changes: Option<Vec<Change<R::State>>>,
+    observers: Vec<Box<dyn Observer<R>>>,
 }
 
 impl<R: Ruleset> Automaton<R> {
@@ -33,9 +187,56 @@ impl<R: Ruleset> Automaton<R> {
                 Grid::new(cols + 2, rows + 2),
                 Grid::new(cols + 2, rows + 2),
             ],
+            generation: 0,
+            stats: Stats::default(),
+            col_population: vec![0; cols],
+            row_population: vec![0; rows],
+            live_hash: 0,
+            history: VecDeque::with_capacity(MAX_CYCLE_PERIOD),
+            cycle: None,
+            changes: None,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Creates an automaton in the state of the snapshot
+    ///
+    /// Neighbor data & statistics are rebuilt from the cells. Births and deaths
+    /// of the last step aren't part of the snapshot and are zero.
+    pub fn from_snapshot(snapshot: &Snapshot<R::State>) -> Self {
+        let mut automaton = Self::new([snapshot.cells.cols(), snapshot.cells.rows()]);
+        automaton.put(&snapshot.cells, 0, 0);
+        automaton.generation = snapshot.generation;
+        automaton
+    }
+
+    pub fn snapshot(&self) -> Snapshot<R::State> {
+        Snapshot {
+            generation: self.generation,
+            cells: self.cells[0].clone(),
         }
     }
 
+    /// Adds an observer that will be notified of events from now on
+    pub fn observe(&mut self, observer: Box<dyn Observer<R>>) {
+        self.observers.push(observer);
+    }
+
+    /// Enables or disables keeping the list of cells changed by each step
+    pub fn track_changes(&mut self, enabled: bool) {
+        self.changes = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns the cells that became live or dead or got older in the last step,
+    /// in row-major order, or nothing if changes aren't tracked
+    ///
+    /// For rulesets whose state is only made of liveness & age, like `Cgol`, this
+    /// is the same list as `Grid::diff` between the two generations. Edits made
+    /// since the last step are not included.
+    pub fn changes(&self) -> &[Change<R::State>] {
+        self.changes.as_deref().unwrap_or_default()
+    }
+
     /// Advances the grid to the next generation
     pub fn step(&mut self) {
         if let ([a], [b]) = self.neighbor_data.split_at_mut(1) {
@@ -44,12 +245,58 @@ impl<R: Ruleset> Automaton<R> {
             unreachable!()
         }
 
+        // Only observers are told about cycles, so there's no need to look for
+        // them without any
+        if !self.observers.is_empty() {
+            if self.history.len() == MAX_CYCLE_PERIOD {
+                self.history.pop_front();
+            }
+            self.history.push_back((self.stats.population, self.live_hash));
+        }
+
+        self.stats.births = 0;
+        self.stats.deaths = 0;
+        self.stats.total_age = 0;
+
+        if let Some(changes) = &mut self.changes {
+            changes.clear();
+        }
+
         for (col, row) in self.cells[0].indices() {
             let current = &self.cells[0][(col, row)];
             let neighbor_data = &self.neighbor_data[0][(col + 1, row + 1)];
 
             let next = R::next(current, neighbor_data);
 
+            let (was_live, is_live) = (R::is_live(current), R::is_live(&next));
+            match (was_live, is_live) {
+                (false, true) => {
+                    self.stats.births += 1;
+                    self.col_population[col] += 1;
+                    self.row_population[row] += 1;
+                    self.live_hash = self.live_hash.wrapping_add(cell_hash(col, row));
+                }
+                (true, false) => {
+                    self.stats.deaths += 1;
+                    self.col_population[col] -= 1;
+                    self.row_population[row] -= 1;
+                    self.live_hash = self.live_hash.wrapping_sub(cell_hash(col, row));
+                }
+                _ => (),
+            }
+            if was_live != is_live {
+                for observer in &mut self.observers {
+                    observer.on_cell_changed(col, row, current, &next);
+                }
+            }
+            let age = R::age(&next);
+            if let Some(changes) = &mut self.changes {
+                if was_live != is_live || R::age(current) != age {
+                    changes.push(Change { col, row, prev: current.clone(), curr: next.clone() });
+                }
+            }
+            self.stats.total_age += age.unwrap_or(0);
+
             if let Some(update_neighbor) = R::update_neighbor(current, &next) {
                 self.update_neighbors(col, row, update_neighbor);
             }
@@ -57,11 +304,37 @@ impl<R: Ruleset> Automaton<R> {
             self.cells[1][(col, row)] = next;
         }
 
+        self.stats.population = self.stats.population + self.stats.births - self.stats.deaths;
+        self.generation += 1;
+
         if let ([a], [b]) = self.cells.split_at_mut(1) {
             a.swap(b);
         } else {
             unreachable!()
         }
+
+        if self.observers.is_empty() {
+            return;
+        }
+
+        let current = (self.stats.population, self.live_hash);
+        let cycle = self.history.iter().rev().position(|&past| past == current).map(|i| i + 1);
+        let new_cycle = cycle.filter(|_| self.cycle != cycle);
+        self.cycle = cycle;
+
+        // Observers get a reference to the automaton, so they are moved out of it
+        // while being notified
+        let mut observers = std::mem::take(&mut self.observers);
+        for observer in &mut observers {
+            observer.on_step(self);
+            if self.stats.population == 0 && self.stats.deaths > 0 {
+                observer.on_population_zero(self);
+            }
+            if let Some(period) = new_cycle {
+                observer.on_cycle_detected(self, period);
+            }
+        }
+        self.observers = observers;
     }
 
     /// Returns the current cell state at the specified coordinates
@@ -76,10 +349,12 @@ impl<R: Ruleset> Automaton<R> {
     {
         let prev = self.cells[0][(col, row)].clone();
         let result = f(&mut self.cells[0][(col, row)]);
+        let curr = self.cells[0][(col, row)].clone();
 
-        if let Some(update_neighbor) = R::update_neighbor(&prev, &self.cells[0][(col, row)]) {
+        if let Some(update_neighbor) = R::update_neighbor(&prev, &curr) {
             self.update_neighbors(col, row, update_neighbor);
         }
+        self.update_stats(col, row, &prev, &curr);
 
         result
     }
@@ -89,11 +364,12 @@ impl<R: Ruleset> Automaton<R> {
     pub fn set_cell(&mut self, col: usize, row: usize, new: R::State) {
         if col >= self.cols() || row >= self.rows() { return }
 
-        let current = &self.cells[0][(col, row)];
+        let current = self.cells[0][(col, row)].clone();
 
-        if let Some(update_neighbor) = R::update_neighbor(current, &new) {
+        if let Some(update_neighbor) = R::update_neighbor(&current, &new) {
             self.update_neighbors(col, row, update_neighbor);
         }
+        self.update_stats(col, row, &current, &new);
 
         self.cells[0][(col, row)] = new;
     }
@@ -110,11 +386,97 @@ impl<R: Ruleset> Automaton<R> {
         }
     }
 
+    /// Changes the size of the board, keeping the cells at the anchor in place
+    ///
+    /// Cells that end up outside the board are removed. Neighbor data & statistics
+    /// are rebuilt like for a snapshot. Observers are notified of the removed
+    /// cells at their old positions, then of the resize.
+    pub fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor) {
+        let [left, top] = anchor.offset([self.cols(), self.rows()], [cols, rows]);
+
+        let mut resized = Self::new([cols, rows]);
+        resized.put(&self.cells[0], left, top);
+        resized.generation = self.generation;
+        resized.changes = self.changes.as_ref().map(|_| Vec::new());
+
+        let mut observers = std::mem::take(&mut self.observers);
+        if !observers.is_empty() {
+            let dead = R::State::default();
+            for ((col, row), cell) in &self.cells[0] {
+                let [col_to, row_to] = [col as isize + left, row as isize + top];
+                let inside = (0..cols as isize).contains(&col_to) && (0..rows as isize).contains(&row_to);
+                if R::is_live(cell) && !inside {
+                    for observer in &mut observers {
+                        observer.on_cell_changed(col, row, cell, &dead);
+                    }
+                }
+            }
+        }
+
+        *self = resized;
+        for observer in &mut observers {
+            observer.on_resized(self, [left, top]);
+        }
+        self.observers = observers;
+    }
+
     pub fn cols(&self) -> usize { self.cells[0].cols() }
     pub fn rows(&self) -> usize { self.cells[0].rows() }
 
     pub fn cells(&self) -> &Grid<R::State> { &self.cells[0] }
 
+    /// Returns the number of steps taken since the automaton was created or cleared
+    pub fn generation(&self) -> u64 { self.generation }
+
+    pub fn stats(&self) -> &Stats { &self.stats }
+
+    /// Returns the bounding box of live cells as `[left, top, right, bottom]`,
+    /// inclusive, or `None` if there are no live cells
+    pub fn bounds(&self) -> Option<[usize; 4]> {
+        let first = |counts: &[usize]| counts.iter().position(|&n| n > 0);
+        let last = |counts: &[usize]| counts.iter().rposition(|&n| n > 0);
+
+        Some([
+            first(&self.col_population)?,
+            first(&self.row_population)?,
+            last(&self.col_population)?,
+            last(&self.row_population)?,
+        ])
+    }
+
+    /// Updates statistics after a cell has been modified outside of `step`
+    fn update_stats(&mut self, col: usize, row: usize, prev: &R::State, curr: &R::State) {
+        let (was_live, is_live) = (R::is_live(prev), R::is_live(curr));
+        match (was_live, is_live) {
+            (false, true) => {
+                self.stats.population += 1;
+                self.col_population[col] += 1;
+                self.row_population[row] += 1;
+                self.live_hash = self.live_hash.wrapping_add(cell_hash(col, row));
+            }
+            (true, false) => {
+                self.stats.population -= 1;
+                self.col_population[col] -= 1;
+                self.row_population[row] -= 1;
+                self.live_hash = self.live_hash.wrapping_sub(cell_hash(col, row));
+            }
+            _ => (),
+        }
+
+        if was_live != is_live {
+            // Earlier generations can't be compared with an edited one
+            self.history.clear();
+            self.cycle = None;
+
+            for observer in &mut self.observers {
+                observer.on_cell_changed(col, row, prev, curr);
+            }
+        }
+
+        self.stats.total_age -= R::age(prev).unwrap_or(0);
+        self.stats.total_age += R::age(curr).unwrap_or(0);
+    }
+
     fn update_neighbors(&mut self, col: usize, row: usize, update: fn(&mut R::NeighborData)) {
         update(&mut self.neighbor_data[1][(col,     row)]);
         update(&mut self.neighbor_data[1][(col + 1, row)]);
@@ -128,10 +490,77 @@ impl<R: Ruleset> Automaton<R> {
         update(&mut self.neighbor_data[1][(col + 2, row + 2)]);
     }
 
+    /// Kills all cells & goes back to generation 0
+    ///
+    /// Observers are notified of each live cell removed, and the changes of the
+    /// last step are forgotten.
     pub fn clear(&mut self) {
+        if !self.observers.is_empty() {
+            let dead = R::State::default();
+            for ((col, row), cell) in &self.cells[0] {
+                if R::is_live(cell) {
+                    for observer in &mut self.observers {
+                        observer.on_cell_changed(col, row, cell, &dead);
+                    }
+                }
+            }
+        }
+
         self.cells[0].clear();
         self.cells[1].clear();
         self.neighbor_data[0].clear();
         self.neighbor_data[1].clear();
+
+        self.generation = 0;
+        self.stats = Stats::default();
+        self.col_population.iter_mut().for_each(|n| *n = 0);
+        self.row_population.iter_mut().for_each(|n| *n = 0);
+        self.live_hash = 0;
+        self.history.clear();
+        self.cycle = None;
+        if let Some(changes) = &mut self.changes {
+            changes.clear();
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<R: Ruleset> serde::Serialize for Automaton<R>
+where
+    R::State: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        // Same layout as `Snapshot` without cloning the cells
+        let mut snapshot = serializer.serialize_struct("Snapshot", 2)?;
+        snapshot.serialize_field("generation", &self.generation)?;
+        snapshot.serialize_field("cells", &self.cells[0])?;
+        snapshot.end()
     }
 }
+
+#[cfg(feature = "serde")]
+impl<'de, R: Ruleset> serde::Deserialize<'de> for Automaton<R>
+where
+    R::State: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Snapshot::deserialize(deserializer).map(|snapshot| Self::from_snapshot(&snapshot))
+    }
+}
+
+/// Mixes the coordinates of a cell into a well distributed hash, the sum of
+/// which identifies a set of cells
+fn cell_hash(col: usize, row: usize) -> u128 {
+    // SplitMix64 finalizer, run twice with different increments for the two
+    // halves of the hash
+    let mix = |increment: u64| {
+        let mut z = ((col as u64) << 32 | row as u64).wrapping_add(increment);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+
+    (mix(0x9e37_79b9_7f4a_7c15) as u128) << 64 | mix(0x6a09_e667_f3bc_c909) as u128
+}
@@ -0,0 +1,30 @@
+use piston_window::*;
+use seagull::render::{Canvas, Color};
+
+/// Canvas drawing to a piston window
+pub struct PistonCanvas<'a, G: Graphics> {
+    pub c: Context,
+    pub g: &'a mut G,
+}
+
+impl<G: Graphics> Canvas for PistonCanvas<'_, G> {
+    fn size(&self) -> [f64; 2] {
+        self.c.get_view_size()
+    }
+
+    fn clear(&mut self, color: Color) {
+        clear(color, self.g);
+    }
+
+    fn rectangle(&mut self, color: Color, rect: [f64; 4]) {
+        rectangle(color, rect, self.c.transform, self.g);
+    }
+
+    fn border(&mut self, color: Color, radius: f64, rect: [f64; 4]) {
+        Rectangle::new_border(color, radius).draw(rect, &self.c.draw_state, self.c.transform, self.g);
+    }
+
+    fn line(&mut self, color: Color, radius: f64, from: [f64; 2], to: [f64; 2]) {
+        line_from_to(color, radius, from, to, self.c.transform, self.g);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    cgol::{patterns::{self, apgcodes}, CgolCell},
+    grid::Grid,
+};
+use std::fmt;
+
+/// Kind of a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Methuselah,
+    Puffer,
+}
+
+impl Category {
+    pub const ALL: [Self; 6] = [
+        Self::StillLife,
+        Self::Oscillator,
+        Self::Spaceship,
+        Self::Gun,
+        Self::Methuselah,
+        Self::Puffer,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::StillLife => "still life",
+            Self::Oscillator => "oscillator",
+            Self::Spaceship => "spaceship",
+            Self::Gun => "gun",
+            Self::Methuselah => "methuselah",
+            Self::Puffer => "puffer",
+        })
+    }
+}
+
+/// A pattern along with what is known about it
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: &'static str,
+    pub category: Category,
+    /// Number of generations after which the pattern repeats, possibly displaced,
+    /// or after which a gun or a puffer repeats its output
+    pub period: Option<usize>,
+    /// Displacement `(dx, dy)` after one period for spaceships and puffers
+    pub speed: Option<(isize, isize)>,
+    pub tags: &'static [&'static str],
+    pub cells: &'static Grid<CgolCell>,
+}
+
+impl Entry {
+    /// Number of live cells in the pattern
+    pub fn population(&self) -> usize {
+        self.cells
+            .into_iter()
+            .filter(|(_, cell)| matches!(cell, CgolCell::Live(_)))
+            .count()
+    }
+
+    /// Returns whether the name or any of the tags contains the given text,
+    /// ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+    }
+}
+
+/// Collection of known patterns
+#[derive(Debug, Clone, Default)]
+pub struct PatternCatalog {
+    entries: Vec<Entry>,
+}
+
+impl PatternCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a catalog of all patterns from `cgol::patterns`
+    pub fn builtin() -> Self {
+        use Category::*;
+
+        let mut catalog = Self::new();
+        let mut add = |
+            name: &'static str,
+            category: Category,
+            period: Option<usize>,
+            speed: Option<(isize, isize)>,
+            tags: &'static [&'static str],
+            cells: &'static Grid<CgolCell>,
+        | {
+            catalog.push(Entry { name, category, period, speed, tags, cells });
+        };
+
+        add("block", StillLife, Some(1), None, &["common", apgcodes::BLOCK_2], &patterns::BLOCK_2);
+        add("beehive", StillLife, Some(1), None, &["common", apgcodes::BEEHIVE], &patterns::BEEHIVE);
+        add("loaf", StillLife, Some(1), None, &["common", apgcodes::LOAF], &patterns::LOAF);
+        add("boat", StillLife, Some(1), None, &["common", apgcodes::BOAT], &patterns::BOAT);
+        add("tub", StillLife, Some(1), None, &["common", apgcodes::TUB], &patterns::TUB);
+
+        add("blinker", Oscillator, Some(2), None, &["common", apgcodes::BLINKER], &patterns::BLINKER);
+        add("toad", Oscillator, Some(2), None, &["common", apgcodes::TOAD], &patterns::TOAD);
+        add("beacon", Oscillator, Some(2), None, &["common", apgcodes::BEACON], &patterns::BEACON);
+        add("pulsar", Oscillator, Some(3), None, &[apgcodes::PULSAR], &patterns::PULSAR);
+
+        add("glider", Spaceship, Some(4), Some((1, 1)),
+            &["common", "diagonal", "c/4", apgcodes::GLIDER], &patterns::GLIDER);
+        add("light-weight spaceship", Spaceship, Some(4), Some((2, 0)),
+            &["lwss", "orthogonal", "c/2", apgcodes::LWSS], &patterns::LWSS);
+        add("middle-weight spaceship", Spaceship, Some(4), Some((2, 0)),
+            &["mwss", "orthogonal", "c/2", apgcodes::MWSS], &patterns::MWSS);
+        add("heavy-weight spaceship", Spaceship, Some(4), Some((2, 0)),
+            &["hwss", "orthogonal", "c/2", apgcodes::HWSS], &patterns::HWSS);
+        add("Schick engine", Spaceship, Some(12), Some((-6, 0)),
+            &["tagalong", "orthogonal", "c/2"], &patterns::SCHICK_ENGINE);
+
+        add("Gosper glider gun", Gun, Some(30), None, &["glider"], &patterns::GOSPER_GLIDER_GUN);
+        add("Simkin glider gun", Gun, Some(120), None, &["glider"], &patterns::SIMKIN_GLIDER_GUN);
+
+        add("R-pentomino", Methuselah, None, None, &["common"], &patterns::R_PENTOMINO);
+        add("acorn", Methuselah, None, None, &[], &patterns::ACORN);
+        add("diehard", Methuselah, None, None, &["vanishing"], &patterns::DIEHARD);
+
+        add("puffer train", Puffer, Some(140), Some((70, 0)), &["orthogonal", "c/2"], &patterns::PUFFER_TRAIN);
+
+        catalog
+    }
+
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the entry with exactly the given name
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Returns the entries satisfying the predicate
+    pub fn filter<'a>(&'a self, predicate: impl Fn(&Entry) -> bool + 'a) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.entries.iter().filter(move |e| predicate(e))
+    }
+
+    pub fn by_category(&self, category: Category) -> impl Iterator<Item = &Entry> {
+        self.filter(move |e| e.category == category)
+    }
+
+    pub fn with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.filter(move |e| e.tags.contains(&tag))
+    }
+
+    /// Returns the entries whose name or tags contain the query, ignoring case
+    pub fn search<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.filter(move |e| e.matches(query))
+    }
+
+    /// Returns the categories that have at least one entry, in the order of
+    /// `Category::ALL`
+    pub fn categories(&self) -> Vec<Category> {
+        Category::ALL
+            .iter()
+            .copied()
+            .filter(|&c| self.by_category(c).next().is_some())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::period::find_period;
+
+    #[test]
+    fn builtin_metadata() {
+        let catalog = PatternCatalog::builtin();
+
+        for entry in catalog.filter(|e| {
+            [Category::StillLife, Category::Oscillator, Category::Spaceship].contains(&e.category)
+        }) {
+            let p = find_period(entry.cells, 60).unwrap();
+            let (dx, dy) = entry.speed.unwrap_or((0, 0));
+
+            assert_eq!(entry.period, Some(p.period), "{}", entry.name);
+            assert_eq!((dx, dy), (p.dx, p.dy), "{}", entry.name);
+        }
+
+        assert_eq!(catalog.get("pulsar").unwrap().population(), 48);
+    }
+
+    #[test]
+    fn search() {
+        let catalog = PatternCatalog::builtin();
+
+        let names = |it: &mut dyn Iterator<Item = &Entry>| it.map(|e| e.name).collect::<Vec<_>>();
+
+        assert_eq!(names(&mut catalog.search("WEIGHT")), [
+            "light-weight spaceship",
+            "middle-weight spaceship",
+            "heavy-weight spaceship",
+        ]);
+        assert_eq!(names(&mut catalog.search("xq4_153")), ["glider"]);
+        assert_eq!(names(&mut catalog.with_tag("diagonal")), ["glider"]);
+        assert_eq!(catalog.by_category(Category::Oscillator).count(), 4);
+        assert_eq!(catalog.categories(), [
+            Category::StillLife,
+            Category::Oscillator,
+            Category::Spaceship,
+            Category::Gun,
+            Category::Methuselah,
+            Category::Puffer,
+        ]);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    apgcode,
+    automaton::Automaton,
+    cgol::{Cgol, CgolCell},
+    grid::{Component, Connectivity, Grid},
+    period::{find_period, live_cells},
+    soup::Soup,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+};
+
+/// Empty space left around the soup on the board
+const PAD: usize = 64;
+/// Distance from the other cells beyond which spaceships moving away from them
+/// are considered to have escaped
+const ESCAPE_MARGIN: usize = 8;
+/// Generations between checks for escaped spaceships
+const CHECK_INTERVAL: usize = 30;
+/// Longest period recognized when detecting stabilization and classifying objects
+const MAX_PERIOD: usize = 60;
+
+/// Tallies objects left over after running random soups to stabilization
+pub struct Census {
+    tallies: HashMap<String, u64>,
+    soups: u64,
+    unstabilized: u64,
+    max_generations: usize,
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Self {
+            tallies: HashMap::new(),
+            soups: 0,
+            unstabilized: 0,
+            max_generations: 10_000,
+        }
+    }
+
+    /// Sets the number of generations after which a soup that hasn't stabilized
+    /// is given up on
+    pub fn max_generations(mut self, max_generations: usize) -> Self {
+        self.max_generations = max_generations;
+        self
+    }
+
+    /// Runs the soup to stabilization and tallies the objects found in its ash
+    ///
+    /// Returns `false` if the soup didn't stabilize in time, in which case nothing
+    /// is tallied except for spaceships that escaped before giving up.
+    pub fn run(&mut self, soup: &Soup) -> bool {
+        self.run_pattern(&soup.generate())
+    }
+
+    /// Same as `run`, but with an arbitrary starting pattern
+    pub fn run_pattern(&mut self, pattern: &Grid<CgolCell>) -> bool {
+        let mut cgol = Automaton::<Cgol>::new([pattern.cols() + 2 * PAD, pattern.rows() + 2 * PAD]);
+        cgol.put(pattern, PAD as isize, PAD as isize);
+
+        self.soups += 1;
+
+        let mut history = VecDeque::with_capacity(MAX_PERIOD);
+        for generation in 0..self.max_generations {
+            if generation % CHECK_INTERVAL == 0 && self.remove_escaped(&mut cgol) {
+                history.clear();
+            }
+
+            let state = live_cells(cgol.cells());
+            if history.contains(&state) {
+                for object in cgol.cells().components(Connectivity::Distance2) {
+                    self.tally(&object.cells);
+                }
+                return true;
+            }
+
+            if history.len() == MAX_PERIOD {
+                history.pop_front();
+            }
+            history.push_back(state);
+
+            cgol.step();
+        }
+
+        self.unstabilized += 1;
+        false
+    }
+
+    /// Tallies and removes spaceships that have escaped, returns whether any
+    /// were found
+    ///
+    /// A spaceship has escaped once it is clear of the bounds of the other cells
+    /// along an axis on which it is moving away from them or not moving at all,
+    /// so that it can never run into them.
+    fn remove_escaped(&mut self, cgol: &mut Automaton<Cgol>) -> bool {
+        let mut objects = cgol
+            .cells()
+            .components(Connectivity::Distance2)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut removed = false;
+
+        for i in 0..objects.len() {
+            let Component { cells: object, left, top } = objects[i].as_ref().unwrap();
+            let period = match find_period(object, MAX_PERIOD).filter(|p| p.is_moving()) {
+                Some(period) => period,
+                None => continue,
+            };
+
+            // Bounds of all the other cells as [left, top, right, bottom], exclusive
+            let others = objects
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(_, other)| other.as_ref())
+                .map(|o| [o.left, o.top, o.left + o.cells.cols(), o.top + o.cells.rows()])
+                .reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]);
+
+            let escaped = match others {
+                Some([l, t, r, b]) => {
+                    let [right, bottom] = [left + object.cols(), top + object.rows()];
+                    let away = |before: bool, after: bool, speed: isize| {
+                        (before && speed <= 0) || (after && speed >= 0)
+                    };
+
+                    away(right + ESCAPE_MARGIN <= l, *left >= r + ESCAPE_MARGIN, period.dx)
+                        || away(bottom + ESCAPE_MARGIN <= t, *top >= b + ESCAPE_MARGIN, period.dy)
+                }
+                None => true,
+            };
+
+            if escaped {
+                let Component { cells: object, left, top } = objects[i].take().unwrap();
+                self.tally(&object);
+
+                for ((col, row), cell) in &object {
+                    if *cell != CgolCell::Dead {
+                        cgol.set_cell(left + col, top + row, CgolCell::Dead);
+                    }
+                }
+                removed = true;
+            }
+        }
+
+        removed
+    }
+
+    fn tally(&mut self, object: &Grid<CgolCell>) {
+        *self.tallies.entry(classify(object)).or_insert(0) += 1;
+    }
+
+    /// Number of soups run so far
+    pub const fn soups(&self) -> u64 { self.soups }
+
+    /// Number of soups that didn't stabilize in time
+    pub const fn unstabilized(&self) -> u64 { self.unstabilized }
+
+    /// Returns how many times each object was found, most common first
+    pub fn tallies(&self) -> Vec<(&str, u64)> {
+        let mut tallies = self.tallies
+            .iter()
+            .map(|(code, &n)| (code.as_str(), n))
+            .collect::<Vec<_>>();
+
+        tallies.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        tallies
+    }
+
+    /// Writes the tallies as plain text, one object per line
+    pub fn write_report(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "# soups: {}", self.soups)?;
+        writeln!(w, "# unstabilized: {}", self.unstabilized)?;
+
+        for (code, n) in self.tallies() {
+            writeln!(w, "{} {}", code, n)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Census {
+    fn default() -> Self { Self::new() }
+}
+
+/// Returns the apgcode of the object, or `zz_` followed by its population if it
+/// isn't periodic on its own
+fn classify(object: &Grid<CgolCell>) -> String {
+    apgcode::encode(object, MAX_PERIOD)
+        .unwrap_or_else(|| format!("zz_{}", live_cells(object).0.len()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns;
+    use std::collections::HashSet;
+
+    #[test]
+    fn objects() {
+        let mut census = Census::new();
+
+        let mut pattern = Grid::new(20, 10);
+        for (src, left, top) in &[
+            (&*patterns::BLOCK_2, 0, 0),
+            (&*patterns::BLINKER, 10, 0),
+            (&*patterns::BEEHIVE, 0, 6),
+            (&patterns::BEEHIVE.transposed(), 10, 5),
+        ] {
+            for (pos, cell) in *src {
+                pattern[(left + pos.0, top + pos.1)] = *cell;
+            }
+        }
+
+        assert!(census.run_pattern(&pattern));
+        assert_eq!(census.tallies(), vec![("xs6_696", 2), ("xp2_7", 1), ("xs4_33", 1)]);
+    }
+
+    #[test]
+    fn escaped_spaceship() {
+        let mut census = Census::new();
+
+        // A glider flying away from a block
+        let mut pattern = Grid::new(8, 8);
+        pattern[(0, 0)] = CgolCell::Live(0);
+        pattern[(1, 0)] = CgolCell::Live(0);
+        pattern[(0, 1)] = CgolCell::Live(0);
+        pattern[(1, 1)] = CgolCell::Live(0);
+        for (pos, cell) in &*patterns::GLIDER {
+            pattern[(5 + pos.0, 5 + pos.1)] = *cell;
+        }
+
+        assert!(census.run_pattern(&pattern));
+        assert_eq!(census.tallies().len(), 2);
+        assert!(census.tallies().contains(&("xq4_153", 1)));
+    }
+
+    /// Runs the pattern on an unbounded plane for the given number of generations
+    /// and classifies whatever is left, so that escaped spaceships never hit an
+    /// edge or get removed early
+    fn reference(pattern: &Grid<CgolCell>, generations: usize) -> Vec<(String, u64)> {
+        let mut live = pattern
+            .into_iter()
+            .filter(|(_, cell)| **cell != CgolCell::Dead)
+            .map(|((col, row), _)| (col as isize, row as isize))
+            .collect::<HashSet<_>>();
+
+        for _ in 0..generations {
+            let mut neighbors = HashMap::new();
+            for &(x, y) in &live {
+                for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))) {
+                    if (dx, dy) != (0, 0) {
+                        *neighbors.entry((x + dx, y + dy)).or_insert(0) += 1;
+                    }
+                }
+            }
+            live = neighbors
+                .into_iter()
+                .filter(|(pos, n)| *n == 3 || (*n == 2 && live.contains(pos)))
+                .map(|(pos, _)| pos)
+                .collect();
+        }
+
+        let left = live.iter().map(|p| p.0).min().unwrap();
+        let top = live.iter().map(|p| p.1).min().unwrap();
+        let right = live.iter().map(|p| p.0).max().unwrap();
+        let bottom = live.iter().map(|p| p.1).max().unwrap();
+
+        let mut ash = Grid::new((right - left + 1) as usize, (bottom - top + 1) as usize);
+        for (x, y) in live {
+            ash[((x - left) as usize, (y - top) as usize)] = CgolCell::Live(0);
+        }
+
+        let mut tallies = HashMap::new();
+        for object in ash.components(Connectivity::Distance2) {
+            *tallies.entry(classify(&object.cells)).or_insert(0) += 1;
+        }
+        let mut tallies = tallies.into_iter().collect::<Vec<_>>();
+        tallies.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        tallies
+    }
+
+    #[test]
+    fn matches_unbounded_run() {
+        // Sends off three gliders and leaves a boat behind
+        let soup = Soup::new(3);
+
+        let mut census = Census::new();
+        assert!(census.run(&soup));
+
+        let tallies = census
+            .tallies()
+            .into_iter()
+            .map(|(code, n)| (code.to_string(), n))
+            .collect::<Vec<_>>();
+        assert_eq!(tallies, reference(&soup.generate(), 3000));
+        assert!(tallies.contains(&("xq4_153".to_string(), 3)));
+        assert!(tallies.contains(&("xs5_253".to_string(), 1)));
+    }
+}
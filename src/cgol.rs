@@ -1,4 +1,4 @@
-use crate::automaton::{Ruleset, UpdateNeighbor};
+use crate::{automaton::{Ruleset, UpdateNeighbor}, snapshot::Encode};
 
 /// Conway's Game of Life ruleset
 pub struct Cgol;
@@ -12,7 +12,7 @@ impl Ruleset for Cgol {
             // Any live cell with two or three live neighbours survives
             (&CgolCell::Live(age), 2..=3) => CgolCell::Live(age.saturating_add(1)),
             // Any dead cell with three live neighbours becomes a live cell
-            (&CgolCell::Dead, &n) if n == 3 => CgolCell::Live(0),
+            (&CgolCell::Dead, 3) => CgolCell::Live(0),
             // All other live cells die in the next generation
             (&CgolCell::Live(_), _) => CgolCell::Dead,
             // Similarly, all other dead cells stay dead
@@ -27,11 +27,36 @@ impl Ruleset for Cgol {
             _ => None,
         }
     }
+
+    fn is_live(s: &Self::State) -> bool {
+        matches!(s, CgolCell::Live(_))
+    }
+
+    fn age(s: &Self::State) -> Option<u64> {
+        match s {
+            &CgolCell::Live(age) => Some(age as u64),
+            CgolCell::Dead => None,
+        }
+    }
+}
+
+impl Encode for Cgol {
+    const RULE: &'static str = "B3/S23";
+
+    fn decode(live: bool, age: Option<u64>) -> Self::State {
+        if live {
+            CgolCell::Live(age.unwrap_or(0).min(u8::MAX as u64) as u8)
+        } else {
+            CgolCell::Dead
+        }
+    }
 }
 
 /// Conway's Game of Life cell state
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CgolCell {
+    #[default]
     Dead,
     Live(u8),
 }
@@ -45,10 +70,6 @@ impl CgolCell {
     }
 }
 
-impl Default for CgolCell {
-    fn default() -> Self { Self::Dead }
-}
-
 pub mod patterns {
     use super::*;
     use crate::grid::Grid;
@@ -158,13 +179,161 @@ pub mod patterns {
                 1, 0, 0, 0, 0, 1,
                 0, 1, 1, 1, 1, 1,
             ]);
+
+        pub static ref HWSS: Grid<CgolCell> =
+            from_slice(7, 5, &[
+                0, 0, 1, 1, 0, 0, 0,
+                1, 0, 0, 0, 0, 1, 0,
+                0, 0, 0, 0, 0, 0, 1,
+                1, 0, 0, 0, 0, 0, 1,
+                0, 1, 1, 1, 1, 1, 1,
+            ]);
+
+        pub static ref SCHICK_ENGINE: Grid<CgolCell> =
+            from_slice(20, 11, &[
+                0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1,
+                0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0,
+                1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0,
+                1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ]);
+
+        // Guns
+        pub static ref GOSPER_GLIDER_GUN: Grid<CgolCell> =
+            from_slice(36, 9, &[
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1,
+                1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 1, 1, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ]);
+
+        pub static ref SIMKIN_GLIDER_GUN: Grid<CgolCell> =
+            from_slice(33, 21, &[
+                1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 1,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 0, 0, 0, 1, 1,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ]);
+
+        // Methuselahs
+        pub static ref R_PENTOMINO: Grid<CgolCell> =
+            from_slice(3, 3, &[
+                0, 1, 1,
+                1, 1, 0,
+                0, 1, 0,
+            ]);
+
+        pub static ref ACORN: Grid<CgolCell> =
+            from_slice(7, 3, &[
+                0, 1, 0, 0, 0, 0, 0,
+                0, 0, 0, 1, 0, 0, 0,
+                1, 1, 0, 0, 1, 1, 1,
+            ]);
+
+        pub static ref DIEHARD: Grid<CgolCell> =
+            from_slice(8, 3, &[
+                0, 0, 0, 0, 0, 0, 1, 0,
+                1, 1, 0, 0, 0, 0, 0, 0,
+                0, 1, 0, 0, 0, 1, 1, 1,
+            ]);
+
+        // Puffers
+        pub static ref PUFFER_TRAIN: Grid<CgolCell> =
+            from_slice(5, 18, &[
+                0, 0, 0, 1, 0,
+                0, 0, 0, 0, 1,
+                1, 0, 0, 0, 1,
+                0, 1, 1, 1, 1,
+                0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0,
+                1, 0, 0, 0, 0,
+                0, 1, 1, 0, 0,
+                0, 0, 1, 0, 0,
+                0, 0, 1, 0, 0,
+                0, 1, 0, 0, 0,
+                0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0,
+                0, 0, 0, 1, 0,
+                0, 0, 0, 0, 1,
+                1, 0, 0, 0, 1,
+                0, 1, 1, 1, 1,
+            ]);
+
+        // Breeders are left out: none small enough to embed here shows its
+        // quadratic growth within the time a unit test can take
+    }
+
+    /// apgcodes of the patterns above, see `crate::apgcode`
+    pub mod apgcodes {
+        // Still lifes
+        pub const BLOCK_2: &str = "xs4_33";
+        pub const BEEHIVE: &str = "xs6_696";
+        pub const LOAF: &str = "xs7_2596";
+        pub const BOAT: &str = "xs5_253";
+        pub const TUB: &str = "xs4_252";
+
+        // Oscillators
+        pub const BLINKER: &str = "xp2_7";
+        pub const TOAD: &str = "xp2_7e";
+        pub const BEACON: &str = "xp2_318c";
+        pub const PULSAR: &str = "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401";
+
+        // Spaceships
+        pub const GLIDER: &str = "xq4_153";
+        pub const LWSS: &str = "xq4_6frc";
+        pub const MWSS: &str = "xq4_27dee6";
+        pub const HWSS: &str = "xq4_27deee6";
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::automaton::Automaton;
+    use crate::{
+        automaton::{Anchor, Automaton, Observer, Ruleset, Stats},
+        grid::Grid,
+        period::{find_period, Period},
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    fn population(cgol: &Automaton<Cgol>) -> usize {
+        cgol.stats().population
+    }
+
+    fn run(cgol: &mut Automaton<Cgol>, generations: usize) {
+        for _ in 0..generations {
+            cgol.step();
+        }
+    }
 
     #[test]
     fn square() {
@@ -202,4 +371,374 @@ mod test {
 
         assert_eq!(cgol.cells(), &state);
     }
+
+    #[test]
+    fn stats() {
+        let mut cgol = Automaton::<Cgol>::new([10, 10]);
+        cgol.put(&patterns::BLINKER, 4, 3);
+
+        assert_eq!(cgol.stats().population, 3);
+        assert_eq!(cgol.bounds(), Some([4, 3, 4, 5]));
+
+        cgol.step();
+        assert_eq!(cgol.generation(), 1);
+        assert_eq!(*cgol.stats(), Stats { population: 3, births: 2, deaths: 2, total_age: 1 });
+        assert_eq!(cgol.bounds(), Some([3, 4, 5, 4]));
+
+        cgol.set_cell(3, 4, CgolCell::Dead);
+        assert_eq!(cgol.stats().population, 2);
+        assert_eq!(cgol.stats().mean_age(), Some(0.5));
+        assert_eq!(cgol.bounds(), Some([4, 4, 5, 4]));
+
+        cgol.clear();
+        assert_eq!(*cgol.stats(), Stats::default());
+        assert_eq!(cgol.bounds(), None);
+
+        // Compare with a full scan after a while of chaotic evolution
+        cgol.put(&crate::soup::Soup::new(0).size(8, 8).generate(), 1, 1);
+        run(&mut cgol, 20);
+
+        let live = cgol.cells().into_iter().filter(|(_, c)| **c != CgolCell::Dead).collect::<Vec<_>>();
+        let ages = live.iter().map(|(_, c)| Cgol::age(c).unwrap()).sum::<u64>();
+        let cols = live.iter().map(|((col, _), _)| *col);
+        let rows = live.iter().map(|((_, row), _)| *row);
+
+        assert_eq!(cgol.stats().population, live.len());
+        assert_eq!(cgol.stats().total_age, ages);
+        assert_eq!(cgol.bounds(), Some([
+            cols.clone().min().unwrap(),
+            rows.clone().min().unwrap(),
+            cols.max().unwrap(),
+            rows.max().unwrap(),
+        ]));
+    }
+
+    #[test]
+    fn changes() {
+        let mut cgol = Automaton::<Cgol>::new([20, 20]);
+        cgol.put(&patterns::R_PENTOMINO, 8, 8);
+        cgol.step();
+        assert_eq!(cgol.changes(), []);
+
+        cgol.track_changes(true);
+        for _ in 0..10 {
+            let prev = cgol.cells().clone();
+            cgol.step();
+
+            let changes = prev.diff(cgol.cells());
+            assert_eq!(cgol.changes(), &changes[..]);
+
+            // Besides births & deaths, all surviving cells got older
+            let survivors = cgol.stats().population - cgol.stats().births;
+            assert_eq!(changes.len(), cgol.stats().births + cgol.stats().deaths + survivors);
+        }
+
+        // Clearing starts over without the changes of the last step
+        cgol.clear();
+        assert_eq!(cgol.changes(), []);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut cgol = Automaton::<Cgol>::new([20, 20]);
+        cgol.put(&patterns::R_PENTOMINO, 8, 8);
+        run(&mut cgol, 10);
+
+        let snapshot = cgol.snapshot();
+        assert_eq!(snapshot.generation, 10);
+
+        let mut restored = Automaton::<Cgol>::from_snapshot(&snapshot);
+        assert_eq!(restored.stats().population, cgol.stats().population);
+        assert_eq!(restored.stats().total_age, cgol.stats().total_age);
+        assert_eq!(restored.bounds(), cgol.bounds());
+
+        run(&mut cgol, 10);
+        run(&mut restored, 10);
+        assert_eq!(restored.snapshot(), cgol.snapshot());
+    }
+
+    #[test]
+    fn resize() {
+        let mut cgol = Automaton::<Cgol>::new([10, 10]);
+        cgol.put(&patterns::GLIDER, 1, 1);
+        run(&mut cgol, 4);
+        assert_eq!(cgol.bounds(), Some([2, 2, 4, 4]));
+
+        cgol.resize(20, 16, Anchor::Center);
+        assert_eq!((cgol.cols(), cgol.rows()), (20, 16));
+        assert_eq!(cgol.bounds(), Some([7, 5, 9, 7]));
+        assert_eq!(cgol.generation(), 4);
+
+        // Neighbors are counted on the new board
+        let mut expected = Automaton::<Cgol>::new([20, 16]);
+        expected.put(&cgol.cells().clone(), 0, 0);
+        run(&mut cgol, 8);
+        run(&mut expected, 8);
+        assert_eq!(cgol.cells(), expected.cells());
+
+        // Cells past the new edges are removed
+        cgol.resize(11, 10, Anchor::TopLeft);
+        assert_eq!(cgol.stats().population, 3);
+        assert_eq!(cgol.bounds(), Some([9, 7, 10, 9]));
+
+        cgol.resize(4, 4, Anchor::BottomRight);
+        assert_eq!(cgol.bounds(), Some([2, 1, 3, 3]));
+
+        assert_eq!(Anchor::Center.offset([5, 5], [2, 2]), [-1, -1]);
+        assert_eq!(Anchor::Right.offset([5, 5], [8, 8]), [3, 1]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut cgol = Automaton::<Cgol>::new([4, 4]);
+        cgol.put(&patterns::BLOCK_2, 1, 1);
+        cgol.step();
+
+        let json = serde_json::to_string(&cgol).unwrap();
+        assert_eq!(json, concat!(
+            r#"{"generation":1,"cells":{"cols":4,"rows":4,"data":["#,
+            r#""Dead","Dead","Dead","Dead","#,
+            r#""Dead",{"Live":1},{"Live":1},"Dead","#,
+            r#""Dead",{"Live":1},{"Live":1},"Dead","#,
+            r#""Dead","Dead","Dead","Dead"]}}"#,
+        ));
+
+        let snapshot = serde_json::from_str::<crate::automaton::Snapshot<CgolCell>>(&json).unwrap();
+        assert_eq!(snapshot, cgol.snapshot());
+
+        let mut restored: Automaton<Cgol> = serde_json::from_str(&json).unwrap();
+        restored.step();
+        cgol.step();
+        assert_eq!(restored.snapshot(), cgol.snapshot());
+
+        let invalid = r#"{"cols":2,"rows":2,"data":["Dead"]}"#;
+        assert!(serde_json::from_str::<Grid<CgolCell>>(invalid).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Step(u64),
+        Changed(usize, usize, bool),
+        PopulationZero(u64),
+        Cycle(u64, usize),
+        Resized([isize; 2]),
+    }
+
+    struct Log(Rc<RefCell<Vec<Event>>>);
+
+    impl Observer<Cgol> for Log {
+        fn on_step(&mut self, cgol: &Automaton<Cgol>) {
+            self.0.borrow_mut().push(Event::Step(cgol.generation()));
+        }
+
+        fn on_cell_changed(&mut self, col: usize, row: usize, _prev: &CgolCell, curr: &CgolCell) {
+            self.0.borrow_mut().push(Event::Changed(col, row, Cgol::is_live(curr)));
+        }
+
+        fn on_population_zero(&mut self, cgol: &Automaton<Cgol>) {
+            self.0.borrow_mut().push(Event::PopulationZero(cgol.generation()));
+        }
+
+        fn on_cycle_detected(&mut self, cgol: &Automaton<Cgol>, period: usize) {
+            self.0.borrow_mut().push(Event::Cycle(cgol.generation(), period));
+        }
+
+        fn on_resized(&mut self, _cgol: &Automaton<Cgol>, offset: [isize; 2]) {
+            self.0.borrow_mut().push(Event::Resized(offset));
+        }
+    }
+
+    #[test]
+    fn observer() {
+        use Event::*;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut cgol = Automaton::<Cgol>::new([5, 5]);
+        cgol.observe(Box::new(Log(events.clone())));
+
+        cgol.put(&patterns::BLINKER, 2, 1);
+        run(&mut cgol, 4);
+        assert_eq!(events.replace(Vec::new()), [
+            Changed(2, 1, true), Changed(2, 2, true), Changed(2, 3, true),
+            Changed(2, 1, false), Changed(1, 2, true), Changed(3, 2, true), Changed(2, 3, false),
+            Step(1),
+            Changed(2, 1, true), Changed(1, 2, false), Changed(3, 2, false), Changed(2, 3, true),
+            Step(2), Cycle(2, 2),
+            Changed(2, 1, false), Changed(1, 2, true), Changed(3, 2, true), Changed(2, 3, false),
+            Step(3),
+            Changed(2, 1, true), Changed(1, 2, false), Changed(3, 2, false), Changed(2, 3, true),
+            Step(4),
+        ]);
+
+        // Editing restarts cycle detection
+        cgol.set_cell(2, 2, CgolCell::Dead);
+        run(&mut cgol, 2);
+        assert_eq!(events.replace(Vec::new()), [
+            Changed(2, 2, false),
+            Changed(2, 1, false), Changed(2, 3, false),
+            Step(5), PopulationZero(5),
+            Step(6), Cycle(6, 1),
+        ]);
+
+        // Cells cut off by a resize die where they were, the rest move
+        cgol.put(&patterns::BLOCK_2, 0, 0);
+        cgol.put(&patterns::BLOCK_2, 3, 3);
+        events.replace(Vec::new());
+        cgol.resize(4, 4, Anchor::TopLeft);
+        assert_eq!(events.replace(Vec::new()), [
+            Changed(4, 3, false), Changed(3, 4, false), Changed(4, 4, false),
+            Resized([0, 0]),
+        ]);
+        cgol.resize(6, 6, Anchor::Center);
+        assert_eq!(events.replace(Vec::new()), [Resized([1, 1])]);
+
+        // Clearing kills the remaining cells
+        cgol.clear();
+        assert_eq!(events.replace(Vec::new()), [
+            Changed(1, 1, false), Changed(2, 1, false), Changed(1, 2, false), Changed(2, 2, false),
+            Changed(4, 4, false),
+        ]);
+    }
+
+    #[test]
+    fn spaceships() {
+        assert_eq!(find_period(&patterns::HWSS, 12), Some(Period { period: 4, dx: 2, dy: 0 }));
+        assert_eq!(find_period(&patterns::SCHICK_ENGINE, 12), Some(Period { period: 12, dx: -6, dy: 0 }));
+    }
+
+    #[test]
+    fn gosper_glider_gun() {
+        // One glider (5 cells) every 30 generations
+        let mut cgol = Automaton::<Cgol>::new([60, 40]);
+        cgol.put(&patterns::GOSPER_GLIDER_GUN, 2, 2);
+
+        for gliders in 0..=4 {
+            assert_eq!(population(&cgol), 36 + 5 * gliders);
+            run(&mut cgol, 30);
+        }
+    }
+
+    #[test]
+    fn simkin_glider_gun() {
+        // One glider every 120 generations
+        let mut cgol = Automaton::<Cgol>::new([90, 90]);
+        cgol.put(&patterns::SIMKIN_GLIDER_GUN, 50, 66);
+
+        for gliders in 0..=3 {
+            assert_eq!(population(&cgol), 36 + 5 * gliders);
+            run(&mut cgol, 120);
+        }
+    }
+
+    #[test]
+    fn diehard() {
+        let mut cgol = Automaton::<Cgol>::new([60, 60]);
+        cgol.put(&patterns::DIEHARD, 25, 25);
+
+        run(&mut cgol, 129);
+        assert_ne!(population(&cgol), 0);
+        run(&mut cgol, 1);
+        assert_eq!(population(&cgol), 0);
+    }
+
+    /// Runs a methuselah placed at `pos` and checks that the part of the board
+    /// within `window` settles into period 2 exactly at `generation` with
+    /// `population` cells
+    ///
+    /// The escaping gliders crash into the edges of the board, so the board
+    /// only has to be large enough for the debris to stay out of the window.
+    fn methuselah(
+        pattern: &Grid<CgolCell>,
+        dims: [usize; 2],
+        [col, row]: [isize; 2],
+        [left, top, right, bottom]: [usize; 4],
+        generation: usize,
+        population: usize,
+    ) {
+        let mut cgol = Automaton::<Cgol>::new(dims);
+        cgol.put(pattern, col, row);
+
+        let window = |cgol: &Automaton<Cgol>| {
+            cgol.cells()
+                .into_iter()
+                .filter(|&((col, row), c)| {
+                    *c != CgolCell::Dead
+                        && (left..=right).contains(&col)
+                        && (top..=bottom).contains(&row)
+                })
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>()
+        };
+
+        run(&mut cgol, generation - 1);
+        let mut states = Vec::new();
+        for _ in 0..4 {
+            states.push(window(&cgol));
+            cgol.step();
+        }
+
+        assert_ne!(states[0], states[2]);
+        assert_eq!(states[1], states[3]);
+        assert_eq!(states[1].len(), population);
+    }
+
+    #[test]
+    fn r_pentomino() {
+        // Stabilizes after 1103 generations with 116 cells, 30 of which are
+        // in the 6 escaping gliders
+        methuselah(&patterns::R_PENTOMINO, [130, 80], [50, 25], [10, 8, 118, 58], 1103, 86);
+    }
+
+    #[test]
+    fn acorn() {
+        // Stabilizes after 5206 generations with 633 cells, which takes too long
+        // in debug builds, so only the first 400 generations are checked against
+        // the populations on an unbounded board, while it still fits on the board
+        let mut cgol = Automaton::<Cgol>::new([100, 100]);
+        cgol.put(&patterns::ACORN, 80, 50);
+
+        for &expected in &[76, 169, 178, 390] {
+            run(&mut cgol, 100);
+            assert_eq!(population(&cgol), expected);
+        }
+    }
+
+    #[test]
+    fn puffer_train() {
+        // The front repeats every 140 generations, 70 cells further right, while
+        // the debris left behind keeps growing
+        let mut cgol = Automaton::<Cgol>::new([240, 110]);
+        cgol.put(&patterns::PUFFER_TRAIN, 10, 40);
+
+        // Live cells within 60 columns of the rightmost one, relative to it
+        let front = |cgol: &Automaton<Cgol>| {
+            let live = cgol.cells()
+                .into_iter()
+                .filter(|(_, c)| **c != CgolCell::Dead)
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>();
+            let right = live.iter().map(|&(col, _)| col).max().unwrap();
+            let mut front = live.into_iter().filter(|&(col, _)| col + 60 > right).collect::<Vec<_>>();
+            let top = front.iter().map(|&(_, row)| row).min().unwrap();
+            for (col, row) in &mut front {
+                *col = right - *col;
+                *row -= top;
+            }
+            front.sort_unstable();
+            (front, right, population(cgol))
+        };
+
+        run(&mut cgol, 140);
+        let mut last = front(&cgol);
+        for _ in 0..2 {
+            run(&mut cgol, 140);
+            let next = front(&cgol);
+
+            assert_eq!(next.0, last.0);
+            assert_eq!(next.1, last.1 + 70);
+            assert!(next.2 > last.2);
+            last = next;
+        }
+    }
 }
@@ -0,0 +1,180 @@
+use seagull::render::{parse_color, Color, Gradient, Palette};
+use std::fs;
+
+/// Location of the config file, which is optional
+pub const PATH: &str = "cgol.conf";
+
+/// Display settings read from the config file
+///
+/// The file consists of `name value` lines, with empty lines and lines starting
+/// with `;` ignored:
+///
+/// ```text
+/// theme light
+/// ages heat
+/// gridlines true
+/// brush #00ff00
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Palette to start with & the name of the theme it's based on
+    pub theme: (String, Palette),
+    pub gridlines: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: ("dark".to_string(), Palette::DARK),
+            gridlines: false,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file, or returns the defaults if there is none
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(path, &text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path, e)),
+        }
+    }
+
+    /// Parses the contents of the config file, whose path is only used in errors
+    fn parse(path: &str, text: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut ages = None;
+        let mut colors: Vec<(&str, Color)> = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            let error = |e: String| format!("{}:{}: {}", path, i + 1, e);
+            let (name, value) = line.split_once(' ').ok_or_else(|| error(format!("missing value for {}", line)))?;
+            let value = value.trim();
+
+            match name {
+                "theme" => {
+                    let palette = value.parse::<Palette>().map_err(error)?;
+                    let name = if value.starts_with('#') { "custom" } else { value };
+                    config.theme = (name.to_string(), palette);
+                }
+                "ages" => ages = Some(value.parse::<Gradient>().map_err(error)?),
+                "gridlines" => {
+                    config.gridlines = value.parse().map_err(|_| error(format!("invalid value for gridlines: {}", value)))?
+                }
+                "brush" | "selection" | "text" => colors.push((name, parse_color(value).map_err(error)?)),
+                _ => return Err(error(format!("unknown setting: {}", name))),
+            }
+        }
+
+        // Colors are applied after the theme regardless of the order of lines
+        let palette = &mut config.theme.1;
+        for (name, [r, g, b, _]) in colors {
+            match name {
+                // The brush is drawn over cells, so it keeps the theme's opacity
+                "brush" => palette.brush = [r, g, b, palette.brush[3]],
+                "selection" => palette.selection = [r, g, b, 1.0],
+                _ => palette.text = [r, g, b, 1.0],
+            }
+            config.theme.0 = "custom".to_string();
+        }
+        palette.ages = ages.unwrap_or_default();
+
+        Ok(config)
+    }
+
+    /// Returns the themes to switch between, the built-in ones and the configured
+    /// one if it differs from them, all without age colors
+    pub fn themes(&self) -> Vec<(String, Palette)> {
+        let mut themes = vec![("dark".to_string(), Palette::DARK), ("light".to_string(), Palette::LIGHT)];
+
+        let (name, palette) = &self.theme;
+        let configured = Palette { ages: Gradient::Fade, ..palette.clone() };
+        if !themes.iter().any(|(_, theme)| *theme == configured) {
+            themes.push((name.clone(), configured));
+        }
+
+        themes
+    }
+
+    /// Returns the age colors to switch between, the built-in ones and the
+    /// configured ones if they differ from them
+    pub fn gradients(&self) -> Vec<Gradient> {
+        let mut gradients = Gradient::builtin();
+        if !gradients.contains(&self.theme.1.ages) {
+            gradients.push(self.theme.1.ages.clone());
+        }
+        gradients
+    }
+}
+
+/// Returns the index of the theme with the same colors as the palette, ignoring
+/// age colors
+pub fn theme_index(themes: &[(String, Palette)], palette: &Palette) -> usize {
+    let palette = Palette { ages: Gradient::Fade, ..palette.clone() };
+    themes.iter().position(|(_, theme)| *theme == palette).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Config, String> {
+        Config::parse(PATH, text)
+    }
+
+    #[test]
+    fn theme() {
+        let config = parse("; comment\n\ntheme light\n").unwrap();
+        assert_eq!(config.theme, ("light".to_string(), Palette::LIGHT));
+        assert!(!config.gridlines);
+
+        let config = parse("theme #ffffff,#000000,#ff0000").unwrap();
+        assert_eq!(config.theme.0, "custom");
+        assert_eq!(config.theme.1.background, [1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn ages() {
+        let config = parse("ages heat\ntheme light").unwrap();
+        assert_eq!(config.theme.1, Palette { ages: Gradient::Heat, ..Palette::LIGHT });
+        assert!(config.gradients().contains(&Gradient::Heat));
+    }
+
+    #[test]
+    fn gridlines() {
+        assert!(parse("gridlines true").unwrap().gridlines);
+        assert_eq!(
+            parse("gridlines maybe").unwrap_err(),
+            "cgol.conf:1: invalid value for gridlines: maybe",
+        );
+    }
+
+    #[test]
+    fn colors() {
+        // Colors apply to the theme even when they come before it
+        let config = parse("brush #00ff00\ntext #ff0000\ntheme light").unwrap();
+        let palette = &config.theme.1;
+        assert_eq!(config.theme.0, "custom");
+        assert_eq!(palette.brush, [0.0, 1.0, 0.0, Palette::LIGHT.brush[3]]);
+        assert_eq!(palette.text, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(palette.selection, Palette::LIGHT.selection);
+        assert_eq!(palette.background, Palette::LIGHT.background);
+
+        // The customized theme can be switched back to
+        assert_eq!(config.themes().len(), 3);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse("\nsize 100").unwrap_err(), "cgol.conf:2: unknown setting: size");
+        assert_eq!(parse("theme").unwrap_err(), "cgol.conf:1: missing value for theme");
+        assert!(parse("theme sepia").unwrap_err().starts_with("cgol.conf:1: "));
+        assert!(parse("brush green").unwrap_err().starts_with("cgol.conf:1: "));
+    }
+}
@@ -1,13 +1,81 @@
 use std::{fmt, iter::Zip, mem, ops::{Index, IndexMut}};
 
+/// Rule deciding which non-default cells belong to the same component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Cells touching by an edge or a corner
+    Eight,
+    /// Cells at most 2 apart in both directions, which is how Life objects are
+    /// usually told apart
+    Distance2,
+}
+
+impl Connectivity {
+    const fn reach(self) -> usize {
+        match self {
+            Self::Eight => 1,
+            Self::Distance2 => 2,
+        }
+    }
+}
+
+/// Connected group of cells cropped out of a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component<T> {
+    pub cells: Grid<T>,
+    /// Position of the top left corner of `cells` in the original grid
+    pub left: usize,
+    pub top: usize,
+}
+
+/// Cell that differs between two states of a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    pub col: usize,
+    pub row: usize,
+    pub prev: T,
+    pub curr: T,
+}
+
 /// Dynamically allocated 2d array
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GridData<T>"))]
 pub struct Grid<T> {
     cols: usize,
     rows: usize,
     data: Box<[T]>,
 }
 
+/// Serialized form of a grid, checked before being turned into one
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GridData<T> {
+    cols: usize,
+    rows: usize,
+    data: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> std::convert::TryFrom<GridData<T>> for Grid<T> {
+    type Error = String;
+
+    fn try_from(grid: GridData<T>) -> Result<Self, Self::Error> {
+        if grid.data.len() != grid.cols * grid.rows {
+            return Err(format!(
+                "expected {} cells for a {}x{} grid, found {}",
+                grid.cols * grid.rows, grid.cols, grid.rows, grid.data.len(),
+            ));
+        }
+
+        Ok(Self {
+            cols: grid.cols,
+            rows: grid.rows,
+            data: grid.data.into(),
+        })
+    }
+}
+
 impl<T> Grid<T> {
     /// Allocates a new grid with default values for each cell
     pub fn new(cols: usize, rows: usize) -> Self
@@ -67,7 +135,210 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Returns the `cols` by `rows` part of the grid with its top left corner at
+    /// `(left, top)`, with default cells where it extends past the grid
+    pub fn crop(&self, left: usize, top: usize, cols: usize, rows: usize) -> Self
+    where
+        T: Default + Clone,
+    {
+        let mut cropped = Self::new(cols, rows);
+        for ((col, row), cell) in &mut cropped {
+            if left + col < self.cols && top + row < self.rows {
+                *cell = self[(left + col, top + row)].clone();
+            }
+        }
+        cropped
+    }
 
+    /// Returns a copy of the grid with rows and columns swapped
+    pub fn transposed(&self) -> Self
+    where
+        T: Default + Clone,
+    {
+        let mut t = Self::new(self.rows, self.cols);
+        for ((col, row), cell) in self {
+            t[(row, col)] = cell.clone();
+        }
+        t
+    }
+
+    /// Returns a copy of the grid mirrored left to right
+    pub fn flipped(&self) -> Self
+    where
+        T: Default + Clone,
+    {
+        let mut f = Self::new(self.cols, self.rows);
+        for ((col, row), cell) in self {
+            f[(self.cols - 1 - col, row)] = cell.clone();
+        }
+        f
+    }
+
+    /// Returns all 8 rotations and reflections of the grid, starting with the
+    /// grid itself
+    pub fn symmetries(&self) -> Vec<Self>
+    where
+        T: Default + Clone,
+    {
+        let mut all = vec![self.clone()];
+        for i in 1..8 {
+            // Alternating reflections about the vertical axis and the diagonal
+            // walk through the whole dihedral group
+            let next = if i % 2 == 1 { all[i - 1].flipped() } else { all[i - 1].transposed() };
+            all.push(next);
+        }
+        all
+    }
+
+    /// Labels each non-default cell with the index of the component it belongs to
+    ///
+    /// Returns the labels along with the number of components. Components are
+    /// numbered in the order of their first cell in row-major order.
+    pub fn labels(&self, connectivity: Connectivity) -> (Grid<Option<usize>>, usize)
+    where
+        T: Default + PartialEq,
+    {
+        let empty = T::default();
+
+        let mut labels = Grid::<Option<usize>>::new(self.cols, self.rows);
+        let mut count = 0;
+
+        for (start, cell) in self {
+            if *cell == empty || labels[start].is_some() {
+                continue;
+            }
+
+            self.flood(start, connectivity, |pos| labels[pos].replace(count).is_none());
+            count += 1;
+        }
+
+        (labels, count)
+    }
+
+    /// Returns the component containing the given cell, or `None` if the cell
+    /// is a default one or outside the grid
+    pub fn component_at(&self, col: usize, row: usize, connectivity: Connectivity) -> Option<Component<T>>
+    where
+        T: Default + Clone + PartialEq,
+    {
+        if col >= self.cols || row >= self.rows || self[(col, row)] == T::default() {
+            return None;
+        }
+
+        let mut visited = Grid::<bool>::new(self.cols, self.rows);
+        let mut cells = Vec::new();
+        self.flood((col, row), connectivity, |pos| {
+            let new = !mem::replace(&mut visited[pos], true);
+            if new {
+                cells.push(pos);
+            }
+            new
+        });
+
+        let left = cells.iter().map(|&(c, _)| c).min()?;
+        let top = cells.iter().map(|&(_, r)| r).min()?;
+        let right = cells.iter().map(|&(c, _)| c).max()?;
+        let bottom = cells.iter().map(|&(_, r)| r).max()?;
+
+        let mut component = Component { cells: Grid::new(right - left + 1, bottom - top + 1), left, top };
+        for (c, r) in cells {
+            component.cells[(c - left, r - top)] = self[(c, r)].clone();
+        }
+
+        Some(component)
+    }
+
+    /// Visits the non-default cells connected to the start cell, with `mark`
+    /// returning whether a cell wasn't visited before
+    fn flood(&self, start: (usize, usize), connectivity: Connectivity, mut mark: impl FnMut((usize, usize)) -> bool)
+    where
+        T: Default + PartialEq,
+    {
+        let empty = T::default();
+        let reach = connectivity.reach();
+
+        mark(start);
+        let mut stack = vec![start];
+
+        while let Some((col, row)) = stack.pop() {
+            for c in col.saturating_sub(reach)..(col + reach + 1).min(self.cols) {
+                for r in row.saturating_sub(reach)..(row + reach + 1).min(self.rows) {
+                    if self[(c, r)] != empty && mark((c, r)) {
+                        stack.push((c, r));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Splits non-default cells into components, each cropped to its bounding box
+    pub fn components(&self, connectivity: Connectivity) -> Vec<Component<T>>
+    where
+        T: Default + Clone + PartialEq,
+    {
+        let (labels, count) = self.labels(connectivity);
+
+        let mut bounds = vec![(usize::MAX, usize::MAX, 0, 0); count];
+        for ((col, row), label) in &labels {
+            if let &Some(i) = label {
+                let (left, top, right, bottom) = &mut bounds[i];
+                *left = (*left).min(col);
+                *top = (*top).min(row);
+                *right = (*right).max(col);
+                *bottom = (*bottom).max(row);
+            }
+        }
+
+        let mut components = bounds
+            .into_iter()
+            .map(|(left, top, right, bottom)| Component {
+                cells: Grid::new(right - left + 1, bottom - top + 1),
+                left,
+                top,
+            })
+            .collect::<Vec<_>>();
+
+        for ((col, row), label) in &labels {
+            if let &Some(i) = label {
+                let c = &mut components[i];
+                c.cells[(col - c.left, row - c.top)] = self[(col, row)].clone();
+            }
+        }
+
+        components
+    }
+
+    /// Returns the cells that differ in the given later state of the grid, in
+    /// row-major order
+    ///
+    /// Between two generations of an automaton this is the same list as
+    /// `Automaton::changes` when cells are only made of liveness & age, as with
+    /// `Cgol`, whose live cells change every step by getting older.
+    pub fn diff(&self, other: &Self) -> Vec<Change<T>>
+    where
+        T: Clone + PartialEq,
+    {
+        self.diff_by(other, |prev, curr| prev != curr)
+    }
+
+    /// Returns the cells for which `changed` returns true given their state in
+    /// this grid and in the given later state of the grid
+    pub fn diff_by(&self, other: &Self, changed: impl Fn(&T, &T) -> bool) -> Vec<Change<T>>
+    where
+        T: Clone,
+    {
+        assert_eq!((self.cols, self.rows), (other.cols, other.rows));
+
+        self.indices()
+            .filter(|&pos| changed(&self[pos], &other[pos]))
+            .map(|(col, row)| Change {
+                col,
+                row,
+                prev: self[(col, row)].clone(),
+                curr: other[(col, row)].clone(),
+            })
+            .collect()
+    }
 
     pub const fn cols(&self) -> usize { self.cols }
     pub const fn rows(&self) -> usize { self.rows }
@@ -136,7 +407,7 @@ impl Iterator for Indices {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.row >= self.rows {
-            return None;
+            None
         } else {
             let item = (self.col, self.row);
 
@@ -195,4 +466,82 @@ mod test {
         assert_eq!(grid[(1, 2)], (1, 2));
         assert_eq!(grid[(2, 2)], (2, 2));
     }
+
+    #[test]
+    fn symmetries() {
+        let grid = Grid::from_slice(3, 2, &[
+            1, 2, 3,
+            4, 5, 6,
+        ]);
+
+        assert_eq!(grid.transposed(), Grid::from_slice(2, 3, &[1, 4, 2, 5, 3, 6]));
+        assert_eq!(grid.flipped(), Grid::from_slice(3, 2, &[3, 2, 1, 6, 5, 4]));
+
+        let all = grid.symmetries();
+        assert_eq!(all.len(), 8);
+        for (i, a) in all.iter().enumerate() {
+            assert!(all[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn crop() {
+        let grid = Grid::from_slice(3, 2, &[1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(grid.crop(1, 0, 2, 2), Grid::from_slice(2, 2, &[2, 3, 5, 6]));
+        assert_eq!(grid.crop(2, 1, 2, 2), Grid::from_slice(2, 2, &[6, 0, 0, 0]));
+        assert_eq!(grid.crop(0, 0, 3, 2), grid);
+    }
+
+    #[test]
+    fn diff() {
+        let a = Grid::from_slice(3, 2, &[0, 1, 2, 3, 4, 5]);
+        let b = Grid::from_slice(3, 2, &[0, 1, 7, 3, 0, 5]);
+
+        assert_eq!(a.diff(&a), vec![]);
+        assert_eq!(a.diff(&b), vec![
+            Change { col: 2, row: 0, prev: 2, curr: 7 },
+            Change { col: 1, row: 1, prev: 4, curr: 0 },
+        ]);
+        assert_eq!(a.diff_by(&b, |x, y| (*x == 0) != (*y == 0)), vec![
+            Change { col: 1, row: 1, prev: 4, curr: 0 },
+        ]);
+    }
+
+    #[test]
+    fn components() {
+        let grid = Grid::from_slice(6, 5, &[
+            1, 1, 0, 0, 0, 0,
+            0, 0, 1, 0, 2, 0,
+            0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0,
+            3, 0, 0, 0, 0, 0,
+        ]);
+
+        let (labels, count) = grid.labels(Connectivity::Eight);
+        assert_eq!(count, 3);
+        assert_eq!(labels[(2, 1)], Some(0));
+        assert_eq!(labels[(4, 1)], Some(1));
+        assert_eq!(labels[(0, 4)], Some(2));
+        assert_eq!(labels[(3, 0)], None);
+
+        let components = grid.components(Connectivity::Distance2);
+        assert_eq!(components, vec![
+            Component {
+                cells: Grid::from_slice(5, 2, &[1, 1, 0, 0, 0, 0, 0, 1, 0, 2]),
+                left: 0,
+                top: 0,
+            },
+            Component {
+                cells: Grid::from_slice(1, 1, &[3]),
+                left: 0,
+                top: 4,
+            },
+        ]);
+
+        assert_eq!(grid.component_at(4, 1, Connectivity::Distance2), Some(components[0].clone()));
+        assert_eq!(grid.component_at(0, 4, Connectivity::Eight), Some(components[1].clone()));
+        assert_eq!(grid.component_at(3, 0, Connectivity::Eight), None);
+        assert_eq!(grid.component_at(6, 0, Connectivity::Eight), None);
+    }
 }
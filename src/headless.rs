@@ -0,0 +1,344 @@
+use seagull::{
+    animation::GifRecorder,
+    automaton::Anchor,
+    census::Census,
+    import::ImageImport,
+    metrics::{Format, Recorder},
+    render::{Gradient, Palette, Renderer},
+    snapshot,
+    soup::{Soup, Symmetry},
+    svg::{Shape, SvgWriter},
+    Automaton, Cgol,
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+/// Options given as `--name value` pairs after the subcommand
+struct Options(HashMap<String, String>);
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument: {}", arg))?;
+            let value = args.next().ok_or_else(|| format!("missing value for --{}", name))?;
+            options.insert(name.to_string(), value.clone());
+        }
+
+        Ok(Self(options))
+    }
+
+    fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        Ok(self.optional(name)?.unwrap_or(default))
+    }
+
+    fn optional<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.0
+            .get(name)
+            .map(|value| value.parse().map_err(|_| format!("invalid value for --{}: {}", name, value)))
+            .transpose()
+    }
+
+    fn path(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+}
+
+/// Dimensions given either as `COLSxROWS` or as a single number for a square
+#[derive(Debug, Clone, Copy)]
+pub struct Size(pub [usize; 2]);
+
+impl FromStr for Size {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n.parse::<usize>().map_err(|_| ());
+
+        match s.split_once('x') {
+            Some((cols, rows)) => Ok(Self([parse(cols)?, parse(rows)?])),
+            None => parse(s).map(|n| Self([n, n])),
+        }
+    }
+}
+
+/// Region of the board given as `LEFT,TOP,COLS,ROWS`
+#[derive(Debug, Clone, Copy)]
+struct Region([usize; 4]);
+
+impl FromStr for Region {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(',').map(|n| n.parse().map_err(|_| ())).collect::<Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [left, top, cols, rows] => Ok(Self([left, top, cols, rows])),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Names of the subcommands accepted by `run`
+pub const SUBCOMMANDS: &[&str] = &["census", "run", "render", "gif", "svg"];
+
+/// Runs a subcommand without opening a window
+pub fn run(args: &[String]) -> Result<(), String> {
+    let (command, args) = args.split_first().ok_or("missing subcommand")?;
+    let options = Options::parse(args)?;
+
+    match command.as_str() {
+        "census" => census(&options),
+        "run" => run_soup(&options),
+        "render" => render(&options),
+        "gif" => gif(&options),
+        "svg" => svg(&options),
+        _ => Err(format!("unknown subcommand: {}", command)),
+    }
+}
+
+/// Returns a soup generator configured by the `--seed`, `--soup-size`,
+/// `--density` and `--symmetry` options
+fn soup(options: &Options, default_size: [usize; 2]) -> Result<Soup, String> {
+    let seed = options.get("seed", 0u64)?;
+    let Size([cols, rows]) = options.get("soup-size", Size(default_size))?;
+    let density = options.get("density", 0.5f64)?;
+    let symmetry = options.get("symmetry", Symmetry::C1)?;
+
+    if !(0.0..=1.0).contains(&density) {
+        return Err(format!("invalid value for --density: {}", density));
+    }
+    if matches!(symmetry, Symmetry::C4 | Symmetry::D8) && cols != rows {
+        return Err(format!("{:?} symmetry requires a square soup", symmetry));
+    }
+
+    Ok(Soup::new(seed).size(cols, rows).density(density).symmetry(symmetry))
+}
+
+/// Returns the automaton to run, either loaded from the snapshot or session file
+/// given by `--input` or with a soup or the pattern of a PNG `--input` centered
+/// on a board of the `--size` given
+///
+/// Loaded boards are only resized if `--size` is given, keeping the cells at the
+/// `--anchor` in place. Images are read as configured by the `--block`, `--threshold` and
+/// `--light-is-live` options.
+fn automaton(options: &Options) -> Result<Automaton<Cgol>, String> {
+    let input = options.path("input");
+    if let Some(path) = input.filter(|path| !path.ends_with(".png")) {
+        let data = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut cgol = snapshot::load(&mut &data[..]).map_err(|e| format!("{}: {}", path, e))?;
+
+        if let Some(Size([cols, rows])) = options.optional("size")? {
+            cgol.resize(cols.max(1), rows.max(1), options.get("anchor", Anchor::Center)?);
+        }
+        return Ok(cgol);
+    }
+
+    let Size([cols, rows]) = options.get("size", Size([200, 200]))?;
+    let pattern = match input {
+        Some(path) => {
+            let import = ImageImport::new()
+                .block(options.get("block", 1usize)?.max(1))
+                .threshold(options.get("threshold", 0.5f32)?.clamp(0.0, 1.0))
+                .light_is_live(options.get("light-is-live", false)?);
+
+            File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| import.read_png(io::BufReader::new(file)))
+                .map_err(|e| format!("{}: {}", path, e))?
+        }
+        None => soup(options, [cols, rows])?.generate(),
+    };
+
+    let (cols, rows) = (cols.max(pattern.cols()), rows.max(pattern.rows()));
+    let mut cgol = Automaton::<Cgol>::new([cols, rows]);
+    cgol.put(
+        &pattern,
+        (cols - pattern.cols()) as isize / 2,
+        (rows - pattern.rows()) as isize / 2,
+    );
+
+    Ok(cgol)
+}
+
+/// Returns the `--cell-size` option, which has to be positive
+fn cell_size(options: &Options, default: f64) -> Result<f64, String> {
+    let cell_size = options.get("cell-size", default)?;
+    if !(cell_size > 0.0 && cell_size.is_finite()) {
+        return Err(format!("invalid value for --cell-size: {}", cell_size));
+    }
+    Ok(cell_size)
+}
+
+/// Returns a renderer configured by the `--cell-size`, `--age`, `--gridlines`,
+/// `--palette` and `--ages` options
+fn renderer(options: &Options) -> Result<Renderer, String> {
+    Ok(Renderer {
+        cell_size: cell_size(options, 4.0)?,
+        show_age: options.get("age", true)?,
+        gridlines: options.get("gridlines", false)?,
+        palette: Palette {
+            ages: options.get("ages", Gradient::Fade)?,
+            ..options.get("palette", Palette::default())?
+        },
+        ..Renderer::default()
+    })
+}
+
+/// Runs consecutively seeded soups and writes a report of the objects found
+fn census(options: &Options) -> Result<(), String> {
+    let soups = options.get("soups", 100u64)?;
+    let max_generations = options.get("max-generations", 10_000usize)?;
+    let soup = soup(options, [16, 16])?;
+
+    let mut census = Census::new().max_generations(max_generations);
+    for i in 0..soups {
+        census.run(&soup.with_seed(soup.seed().wrapping_add(i)));
+    }
+
+    let result = match options.path("output") {
+        Some(path) => File::create(path).and_then(|mut f| census.write_report(&mut f)),
+        None => census.write_report(&mut io::stdout()),
+    };
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Runs a single soup for a number of generations, optionally recording metrics
+/// of each generation
+fn run_soup(options: &Options) -> Result<(), String> {
+    let generations = options.get("generations", 1000u64)?;
+    let mut cgol = automaton(options)?;
+
+    let mut recorder = match options.path("metrics") {
+        Some(path) => {
+            let default = if path.ends_with(".jsonl") { Format::JsonLines } else { Format::Csv };
+            let format = options.get("metrics-format", default)?;
+            let file = File::create(path).map_err(|e| e.to_string())?;
+            Some(Recorder::new(BufWriter::new(file), format))
+        }
+        None => None,
+    };
+
+    let mut record = |cgol: &Automaton<Cgol>| match &mut recorder {
+        Some(recorder) => recorder.record(cgol).map_err(|e| e.to_string()),
+        None => Ok(()),
+    };
+
+    record(&cgol)?;
+    for _ in 0..generations {
+        cgol.step();
+        record(&cgol)?;
+    }
+
+    if let Some(recorder) = &mut recorder {
+        recorder.flush().map_err(|e| e.to_string())?;
+    }
+
+    println!("generation {}: population {}", cgol.generation(), cgol.stats().population);
+    Ok(())
+}
+
+/// Runs a soup or a saved pattern and writes every `--every`th generation as
+/// a numbered PNG file to the `--output` directory
+fn render(options: &Options) -> Result<(), String> {
+    let generations = options.get("generations", 0u64)?;
+    let every = options.get("every", 1u64)?.max(1);
+    let output = Path::new(options.path("output").unwrap_or("."));
+
+    let renderer = renderer(options)?;
+
+    let mut cgol = automaton(options)?;
+    fs::create_dir_all(output).map_err(|e| e.to_string())?;
+
+    let mut steps = 0;
+    for frame in 1.. {
+        let path = output.join(format!("frame_{:05}.png", frame));
+        let file = File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        renderer
+            .render(cgol.cells())
+            .write_png(BufWriter::new(file))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if steps + every > generations {
+            break;
+        }
+        for _ in 0..every {
+            cgol.step();
+        }
+        steps += every;
+    }
+
+    Ok(())
+}
+
+/// Runs a soup or a saved pattern and records generations `--from` to `--to`
+/// as an animated GIF
+fn gif(options: &Options) -> Result<(), String> {
+    let from = options.get("from", 0u64)?;
+    let to = options.get("to", 100u64)?;
+    let delay = options.get("delay", 100u64)?;
+    let path = options.path("output").ok_or("missing --output")?;
+
+    if to < from {
+        return Err(format!("--to {} is before --from {}", to, from));
+    }
+
+    let mut cgol = automaton(options)?;
+    let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut recorder = GifRecorder::new(BufWriter::new(file))
+        .renderer(renderer(options)?)
+        .delay(Duration::from_millis(delay));
+
+    if let Some(Region(region)) = options.optional("region")? {
+        recorder = recorder.region(region);
+    }
+
+    for _ in 0..from {
+        cgol.step();
+    }
+    for generation in from..=to {
+        recorder.record(cgol.cells()).map_err(|e| format!("{}: {}", path, e))?;
+        if generation < to {
+            cgol.step();
+        }
+    }
+
+    recorder.finish().map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Runs a soup or a saved pattern for `--generations` and writes the result as
+/// an SVG image
+fn svg(options: &Options) -> Result<(), String> {
+    let generations = options.get("generations", 0u64)?;
+    let path = options.path("output").ok_or("missing --output")?;
+
+    let mut writer = SvgWriter::new()
+        .cell_size(cell_size(options, 10.0)?)
+        .shape(options.get("shape", Shape::Square)?)
+        .gridlines(options.get("gridlines", false)?)
+        .palette(options.get("palette", Palette::LIGHT)?);
+
+    if let Some(step) = options.optional::<usize>("labels")? {
+        writer = writer.labels(step.max(1));
+    }
+    if let Some(Region(region)) = options.optional("highlight")? {
+        writer = writer.highlight(region, [1.0, 0.5, 0.0, 1.0]);
+    }
+
+    let mut cgol = automaton(options)?;
+    for _ in 0..generations {
+        cgol.step();
+    }
+
+    let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+    writer.write(cgol.cells(), BufWriter::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
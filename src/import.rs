@@ -0,0 +1,166 @@
+use crate::{cgol::CgolCell, grid::Grid, render::Framebuffer};
+use std::io::Read;
+
+/// Settings for turning images into patterns, where each `block` by `block`
+/// square of pixels becomes one cell
+#[derive(Debug, Clone)]
+pub struct ImageImport {
+    block: usize,
+    threshold: f32,
+    light_is_live: bool,
+}
+
+impl Default for ImageImport {
+    fn default() -> Self {
+        Self {
+            block: 1,
+            threshold: 0.5,
+            light_is_live: false,
+        }
+    }
+}
+
+impl ImageImport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of pixels along each side of a cell
+    pub fn block(mut self, block: usize) -> Self {
+        assert!(block > 0);
+        self.block = block;
+        self
+    }
+
+    /// Sets how much of a block has to be covered to become a live cell, between
+    /// 0 and 1
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        assert!((0.0..=1.0).contains(&threshold));
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets whether light pixels are live instead of dark ones, as in images
+    /// drawn in white on black
+    pub fn light_is_live(mut self, light_is_live: bool) -> Self {
+        self.light_is_live = light_is_live;
+        self
+    }
+
+    /// Reads a PNG file as a pattern
+    pub fn read_png(&self, reader: impl Read) -> Result<Grid<CgolCell>, String> {
+        Framebuffer::read_png(reader).map(|image| self.pattern(&image))
+    }
+
+    /// Returns the cells of which enough of the block is covered by live pixels,
+    /// with transparent pixels counting as dead
+    pub fn pattern(&self, image: &Framebuffer) -> Grid<CgolCell> {
+        blocks(image, self.block, |[r, g, b, a]| {
+            let lightness = (0.299 * r + 0.587 * g + 0.114 * b) / 255.0;
+            let coverage = if self.light_is_live { lightness } else { 1.0 - lightness };
+
+            if coverage * a / 255.0 >= self.threshold {
+                CgolCell::Live(0)
+            } else {
+                CgolCell::Dead
+            }
+        })
+    }
+
+    /// Returns the state of each block whose color is closest to the block's
+    /// average color, for rulesets with more states than live & dead
+    pub fn map_palette<S: Default + Clone>(&self, image: &Framebuffer, palette: &[([u8; 3], S)]) -> Grid<S> {
+        blocks(image, self.block, |[r, g, b, _]| {
+            let distance = |[pr, pg, pb]: [u8; 3]| {
+                (r - pr as f32).powi(2) + (g - pg as f32).powi(2) + (b - pb as f32).powi(2)
+            };
+
+            palette
+                .iter()
+                .min_by(|(a, _), (b, _)| distance(*a).total_cmp(&distance(*b)))
+                .map(|(_, state)| state.clone())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Returns the states of blocks of the image given their average RGBA color,
+/// with blocks cut off by the edges of the image averaged over the pixels they
+/// contain
+fn blocks<S: Default + Clone>(image: &Framebuffer, block: usize, state: impl Fn([f32; 4]) -> S) -> Grid<S> {
+    let cols = image.width().div_ceil(block);
+    let rows = image.height().div_ceil(block);
+    let mut grid = Grid::new(cols, rows);
+
+    for ((col, row), cell) in &mut grid {
+        let xs = col * block..((col + 1) * block).min(image.width());
+        let ys = row * block..((row + 1) * block).min(image.height());
+
+        let mut sum = [0.0; 4];
+        for y in ys.clone() {
+            for x in xs.clone() {
+                let pixel = image.pixel(x, y);
+                for (sum, channel) in sum.iter_mut().zip(pixel.iter()) {
+                    *sum += *channel as f32;
+                }
+            }
+        }
+
+        let count = (xs.len() * ys.len()) as f32;
+        *cell = state(sum.map(|c| c / count));
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::render::Canvas;
+
+    fn png(image: &Framebuffer) -> Vec<u8> {
+        let mut data = Vec::new();
+        image.write_png(&mut data).unwrap();
+        data
+    }
+
+    fn cells(grid: &Grid<CgolCell>) -> Vec<u8> {
+        grid.into_iter().map(|(_, cell)| matches!(cell, CgolCell::Live(_)) as u8).collect()
+    }
+
+    #[test]
+    fn threshold() {
+        let mut image = Framebuffer::new(6, 4);
+        image.clear([1.0, 1.0, 1.0, 1.0]);
+        image.rectangle([0.0, 0.0, 0.0, 1.0], [0.0, 0.0, 2.0, 2.0]);
+        image.rectangle([0.0, 0.0, 0.0, 1.0], [2.0, 2.0, 1.0, 2.0]);
+        image.rectangle([0.0, 0.0, 0.0, 1.0], [4.0, 2.0, 1.0, 1.0]);
+        let data = png(&image);
+        assert_eq!(Framebuffer::read_png(&data[..]), Ok(image.clone()));
+
+        let pattern = ImageImport::new().block(2).read_png(&data[..]).unwrap();
+        assert_eq!((pattern.cols(), pattern.rows()), (3, 2));
+        assert_eq!(cells(&pattern), [1, 0, 0, 0, 1, 0]);
+
+        let sparse = ImageImport::new().block(2).threshold(0.2).pattern(&image);
+        assert_eq!(cells(&sparse), [1, 0, 0, 0, 1, 1]);
+
+        let inverted = ImageImport::new().light_is_live(true).pattern(&image);
+        assert_eq!(cells(&inverted)[..6], [0, 0, 1, 1, 1, 1]);
+
+        // Transparent pixels are dead either way
+        assert_eq!(cells(&ImageImport::new().pattern(&Framebuffer::new(2, 1))), [0, 0]);
+        assert_eq!(cells(&ImageImport::new().light_is_live(true).pattern(&Framebuffer::new(2, 1))), [0, 0]);
+    }
+
+    #[test]
+    fn palette() {
+        let mut image = Framebuffer::new(3, 1);
+        image.rectangle([1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]);
+        image.rectangle([0.1, 0.9, 0.0, 1.0], [1.0, 0.0, 1.0, 1.0]);
+
+        let palette = [([0, 0, 0], 0), ([255, 0, 0], 1), ([0, 255, 0], 2)];
+        let states = ImageImport::new().map_palette(&image, &palette);
+        assert_eq!(states, Grid::from_slice(3, 1, &[1, 2, 0]));
+    }
+}
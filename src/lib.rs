@@ -1,6 +1,18 @@
+pub mod animation;
+pub mod apgcode;
 pub mod automaton;
+pub mod catalog;
+pub mod census;
 pub mod cgol;
 pub mod grid;
+pub mod import;
+pub mod metrics;
+pub mod period;
+pub mod render;
+pub mod snapshot;
+pub mod soup;
+pub mod svg;
+pub mod terminal;
 
 pub use automaton::Automaton;
 pub use cgol::Cgol;
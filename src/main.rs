@@ -1,49 +1,269 @@
 use piston_window::*;
 use seagull::{
+    animation::GifRecorder,
+    apgcode,
+    automaton::Anchor,
+    catalog::PatternCatalog,
     cgol::{self, CgolCell},
-    grid::Grid,
+    grid::{Component, Connectivity, Grid},
+    import::ImageImport,
+    metrics::Metrics,
+    period::find_period,
+    render::{Canvas, Mipmap, Palette, Renderer},
+    soup::Soup,
     Automaton, Cgol,
 };
-use std::time::{Duration, Instant};
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    time::{Duration, Instant},
+};
+
+mod headless;
+
+mod canvas;
+use canvas::PistonCanvas;
+
+mod session;
 
-mod renderer;
-use renderer::Renderer;
+mod config;
+use config::Config;
+
+mod tui;
 
 mod utils;
 use utils::RangeExt;
 
-type Brush<'a> = (&'static str, &'a Grid<CgolCell>);
+type Brush<'a> = (&'a str, &'a Grid<CgolCell>);
+
+/// Sizes the board can be resized to in the GUI
+const BOARD_SIZE_RANGE: std::ops::RangeInclusive<usize> = 16..=4096;
+
+/// Directory from which PNG images are loaded as brushes
+const BRUSHES_DIR: &str = "brushes";
+
+/// Reads the pattern drawn in a black on white PNG image
+fn read_image(path: &str) -> Result<Grid<CgolCell>, String> {
+    File::open(path)
+        .map_err(|e| e.to_string())
+        .and_then(|file| ImageImport::new().read_png(BufReader::new(file)))
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Returns the patterns of all images in `BRUSHES_DIR` named after their files
+fn imported_brushes() -> Vec<(String, Grid<CgolCell>)> {
+    let entries = match fs::read_dir(BRUSHES_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut brushes = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            match read_image(&path.to_string_lossy()) {
+                Ok(pattern) => Some((name, pattern)),
+                Err(e) => {
+                    eprintln!("warning: {}", e);
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    brushes.sort_by(|a, b| a.0.cmp(&b.0));
+    brushes
+}
+
+/// Groups the built-in & imported brushes by category, the first group only
+/// holds the single cell brush which toggles cells instead of placing them
+fn brush_categories<'a>(
+    catalog: &'a PatternCatalog,
+    imported: &'a [(String, Grid<CgolCell>)],
+) -> Vec<(String, Vec<Brush<'a>>)> {
+    let mut brushes = vec![("cell".to_string(), vec![("1x1", &*cgol::patterns::BLOCK_1)])];
+
+    brushes.extend(catalog.categories().into_iter().map(|category| {
+        let entries = catalog.by_category(category).map(|e| (e.name, e.cells)).collect();
+        (category.to_string(), entries)
+    }));
+
+    if !imported.is_empty() {
+        brushes.push(("imported".to_string(), imported.iter().map(|(name, cells)| (name.as_str(), cells)).collect()));
+    }
+
+    brushes
+}
+
+/// Returns the indices of the category & brush saved with a session, if they
+/// still exist
+fn saved_brush(brushes: &[(String, Vec<Brush>)], settings: &session::Settings) -> Option<(usize, usize)> {
+    let category = brushes.iter().position(|(c, _)| Some(c) == settings.category.as_ref())?;
+    let brush = settings
+        .brush
+        .as_ref()
+        .and_then(|name| brushes[category].1.iter().position(|(b, _)| b == name))
+        .unwrap_or(0);
+    Some((category, brush))
+}
+
+/// Returns the cell under the cursor, if the cursor is on the board
+fn on_board(cgol: &Automaton<Cgol>, [col, row]: [isize; 2]) -> Option<[usize; 2]> {
+    let inside = (0..cgol.cols() as isize).contains(&col) && (0..cgol.rows() as isize).contains(&row);
+    inside.then_some([col as usize, row as usize])
+}
+
+/// Places the brush centered on the cursor, or toggles the cell under the cursor
+///
+/// Nothing happens while the cursor is off the board, which it may be when the
+/// board doesn't fill the window. Brushes near the edges are cut off at them.
+fn paint(cgol: &mut Automaton<Cgol>, brush: &Grid<CgolCell>, toggle: bool, cursor: [isize; 2]) {
+    let [col, row] = match on_board(cgol, cursor) {
+        Some(cell) => cell,
+        None => return,
+    };
+
+    if toggle {
+        cgol.with_cell_mut(col, row, |cell| cell.toggle());
+    } else {
+        cgol.put(brush, cursor[0] - brush.cols() as isize / 2, cursor[1] - brush.rows() as isize / 2);
+    }
+}
+
+/// Returns the automaton to start with, either the pattern of the PNG image
+/// centered on an empty board or the session if its file exists
+///
+/// Boards are 200x200 by default, sessions keep their size unless one is given.
+fn initial_state(
+    image: Option<&str>,
+    session_path: &str,
+    size: Option<[usize; 2]>,
+) -> Result<(Automaton<Cgol>, session::Settings), String> {
+    let dims = size.unwrap_or([200, 200]);
+
+    if let Some(path) = image {
+        let pattern = read_image(path)?;
+        let dims = [dims[0].max(pattern.cols()), dims[1].max(pattern.rows())];
+        let mut cgol = Automaton::new(dims);
+        cgol.put(
+            &pattern,
+            (dims[0] - pattern.cols()) as isize / 2,
+            (dims[1] - pattern.rows()) as isize / 2,
+        );
+        Ok((cgol, session::Settings::default()))
+    } else if std::path::Path::new(session_path).exists() {
+        let (mut cgol, settings) = session::load(session_path)?;
+        if let Some([cols, rows]) = size {
+            cgol.resize(cols, rows, Anchor::Center);
+        }
+        Ok((cgol, settings))
+    } else {
+        Ok((Automaton::new(dims), session::Settings::default()))
+    }
+}
+
+/// Resizes the board around its center, moving the camera along so the cells
+/// stay in place in the window
+fn resize_board(cgol: &mut Automaton<Cgol>, renderer: &mut Renderer, [cols, rows]: [usize; 2]) {
+    let [cols, rows] = [BOARD_SIZE_RANGE.clamp(cols), BOARD_SIZE_RANGE.clamp(rows)];
+    let [x, y] = Anchor::Center.offset([cgol.cols(), cgol.rows()], [cols, rows]);
+
+    cgol.resize(cols, rows, Anchor::Center);
+    renderer.camera.origin[0] += x as f64;
+    renderer.camera.origin[1] += y as f64;
+}
 
 fn main() {
-    let brushes: &[Brush] = &[
-        ("1x1", &cgol::patterns::BLOCK_1),
-        ("2x2", &cgol::patterns::BLOCK_2),
-        ("beehive", &cgol::patterns::BEEHIVE),
-        ("loaf", &cgol::patterns::LOAF),
-        ("boat", &cgol::patterns::BOAT),
-        ("tub", &cgol::patterns::TUB),
-
-        ("blinker", &cgol::patterns::BLINKER),
-        ("toad", &cgol::patterns::TOAD),
-        ("beacon", &cgol::patterns::BEACON),
-        ("pulsar", &cgol::patterns::PULSAR),
-
-        ("glider", &cgol::patterns::GLIDER),
-        ("light-weight spaceship", &cgol::patterns::LWSS),
-        ("middle-weight spaceship", &cgol::patterns::MWSS),
-    ];
-
-    let dims = [200, 200];
-    let mut cgol = Automaton::<Cgol>::new(dims);
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let tui = args.first().is_some_and(|command| command == "tui");
+    if tui {
+        args.remove(0);
+    }
+    if args.first().is_some_and(|command| headless::SUBCOMMANDS.contains(&command.as_str())) {
+        if let Err(e) = headless::run(&args) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
+    let usage = || {
+        eprintln!(
+            "usage: cgol [tui] [--size COLSxROWS] [FILE] | cgol <{}> [--OPTION VALUE]...",
+            headless::SUBCOMMANDS.join("|"),
+        );
+        std::process::exit(1);
+    };
+
+    let mut size = None;
+    if let Some(i) = args.iter().position(|arg| arg == "--size") {
+        match args.get(i + 1).and_then(|value| value.parse::<headless::Size>().ok()) {
+            Some(headless::Size([cols, rows])) if cols > 0 && rows > 0 => size = Some([cols, rows]),
+            _ => usage(),
+        }
+        args.drain(i..i + 2);
+    }
+    if args.len() > 1 {
+        usage();
+    }
+
+    // The session is loaded from & saved to the given file or the default location,
+    // images are placed on an empty board instead
+    let image = args.first().filter(|path| path.ends_with(".png"));
+    let session_path = match image {
+        Some(_) => session::DEFAULT_PATH,
+        None => args.first().map_or(session::DEFAULT_PATH, String::as_str),
+    }
+    .to_string();
+
+    let catalog = PatternCatalog::builtin();
+    let imported = imported_brushes();
+    let brushes = brush_categories(&catalog, &imported);
+
+    let started = Config::load(config::PATH).and_then(|config| {
+        let (cgol, settings) = initial_state(image.map(String::as_str), &session_path, size)?;
+        if tui {
+            tui::run(cgol, settings, &config, &brushes, &session_path)
+        } else {
+            gui(cgol, settings, &config, &brushes, &session_path);
+            Ok(())
+        }
+    });
+    if let Err(e) = started {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Runs the automaton in a window
+fn gui(
+    mut cgol: Automaton<Cgol>,
+    settings: session::Settings,
+    config: &Config,
+    brushes: &[(String, Vec<Brush>)],
+    session_path: &str,
+) {
     let mut renderer = Renderer {
-        cell_size: 4.0,
-        show_age: true,
+        palette: config.theme.1.clone(),
+        gridlines: config.gridlines,
+        ..Renderer::default()
     };
 
+    let themes = config.themes();
+    let mut theme_idx = config::theme_index(&themes, &renderer.palette);
+    let gradients = config.gradients();
+    let mut gradient_idx = gradients.iter().position(|g| *g == renderer.palette.ages).unwrap_or(0);
+
+    // Large boards are zoomed out to fit a window of at most 800x800 pixels
+    let board = |cgol: &Automaton<Cgol>| [0, 0, cgol.cols(), cgol.rows()];
+    let mut window_size = renderer.window_size([cgol.cols(), cgol.rows()]).map(|n| n.min(800.0));
+    renderer.fit(board(&cgol), window_size);
+
     let mut window: PistonWindow =
-        WindowSettings::new("Conway's Game of Life", renderer.window_size(dims))
-            .resizable(false)
+        WindowSettings::new("Conway's Game of Life", window_size)
+            .resizable(true)
             .build()
             .unwrap();
 
@@ -52,46 +272,133 @@ fn main() {
     let mut font = Glyphs::from_bytes(font_data, texture_ctx, TextureSettings::new()).unwrap();
 
     let mut running = false;
-    let mut cursor = [0usize; 2];
+    let mut cursor = [0isize; 2];
     let mut last_update = Instant::now();
-    let mut generation = 0u32;
 
     let mut step_millis = 64;
     let step_millis_range = 16..=1024;
+    let mut category_idx: usize = 0;
     let mut brush_idx: usize = 0;
+    let mut soup_seed = None;
+    let mut selection: Option<(Component<CgolCell>, String)> = None;
+    let mut ctrl = false;
+    let mut shift = false;
+    // Last position of the mouse in the window & whether dragging it pans the view
+    let mut mouse = [0.0; 2];
+    let mut panning = false;
+    let mut session_status = String::new();
+
+    // Restores settings saved with a session, keeping the current ones if missing
+    let apply = |settings: session::Settings, step_millis: &mut u64, category_idx: &mut usize, brush_idx: &mut usize| {
+        if let Some(millis) = settings.step_millis {
+            *step_millis = step_millis_range.clamp(millis);
+        }
+        if let Some((category, brush)) = saved_brush(brushes, &settings) {
+            *category_idx = category;
+            *brush_idx = brush;
+        }
+    };
+    apply(settings, &mut step_millis, &mut category_idx, &mut brush_idx);
+
+    // Metrics of the last generations shown in the graph
+    let graph_length = 200;
+    let mut history = VecDeque::with_capacity(graph_length);
+    let mut show_graph = false;
+
+    // Path of the GIF being recorded & its recorder
+    let mut recording: Option<(String, GifRecorder<BufWriter<File>>)> = None;
+    let mut recording_status = "off".to_string();
+
+    // Live cell counts drawn when zoomed out, kept until the board changes
+    let mut mipmap: Option<Mipmap> = None;
 
     while let Some(event) = window.next() {
         window.draw_2d(&event, |c, g, device| {
-            clear([0.0, 0.0, 0.0, 1.0], g);
+            let mut canvas = PistonCanvas { c, g };
+            canvas.clear(renderer.palette.background);
+
+            if renderer.scale() < 1.0 {
+                let mipmap = mipmap.get_or_insert_with(|| Mipmap::new(cgol.cells()));
+                renderer.draw_mipmap(mipmap, &mut canvas);
+            } else {
+                renderer.draw_grid(cgol.cells(), &mut canvas);
+            }
+            renderer.draw_bounds([cgol.cols(), cgol.rows()], &mut canvas);
+            let (category, category_brushes) = &brushes[category_idx];
+            let brush = category_brushes[brush_idx];
 
-            renderer.draw_grid(cgol.cells(), c, g);
-            renderer.draw_brush(brushes[brush_idx].1, cursor, c, g);
+            renderer.draw_brush(brush.1, cursor, &mut canvas);
+            if let Some((object, _)) = &selection {
+                renderer.draw_selection(object, &mut canvas);
+            }
+
+            if show_graph {
+                let [width, height] = window_size;
+                renderer.draw_graph(&history, [width - 210.0, height - 110.0, 200.0, 100.0], &mut canvas);
+            }
 
             // Draw info
             let info = format!(
                 concat!(
                     "  [Space] {}\n",
                     "[Up/Down] step:     {}ms\n",
+                    "      [G] category: {}\n",
                     "      [B] brush:    {}\n",
                     "      [A] show age: {:?}\n",
+                    "      [K] ages:     {}\n",
+                    "      [T] theme:    {}\n",
+                    "      [L] grid:     {:?}\n",
+                    "      [P] graph:    {:?}\n",
+                    "  [Wheel] zoom:     {:.2}x\n",
+                    "   [Drag] pan (Shift+Left or Middle)\n",
+                    "      [F] fit pattern\n",
+                    "      [H] fit board\n",
+                    "    [-/=] board:    {}x{}\n",
+                    "      [W] fit board to window\n",
+                    "      [V] record:   {}\n",
                     "      [R] randomize\n",
                     "      [C] clear\n",
+                    "  [Right] select object\n",
+                    " [Ctrl+S] save session\n",
+                    " [Ctrl+O] load session\n",
                     "\n",
                     "generation: {}\n",
+                    "population: {} (+{} -{})\n",
+                    "mean age:   {}\n",
+                    "soup seed:  {}\n",
+                    "selected:   {}\n",
+                    "session:    {} {}\n",
                 ),
                 if running { "running" } else { "paused" },
                 step_millis,
-                brushes[brush_idx].0,
+                category,
+                brush.0,
                 renderer.show_age,
-                generation,
+                renderer.palette.ages.name(),
+                themes[theme_idx].0,
+                renderer.gridlines,
+                show_graph,
+                renderer.camera.zoom,
+                cgol.cols(),
+                cgol.rows(),
+                recording.as_ref().map_or_else(|| recording_status.clone(), |(path, _)| format!("on ({})", path)),
+                cgol.generation(),
+                cgol.stats().population,
+                cgol.stats().births,
+                cgol.stats().deaths,
+                cgol.stats().mean_age().map_or("-".to_string(), |age| format!("{:.1}", age)),
+                soup_seed.map_or("-".to_string(), |s: u64| s.to_string()),
+                selection.as_ref().map_or("-", |(_, code)| code),
+                session_path,
+                session_status,
             );
 
-            let text_color = [0.6, 0.7, 1.0, 1.0];
+            let text_color = renderer.palette.text;
             for (i, line) in info.lines().enumerate() {
                 text(
                     text_color, 10, line, &mut font,
                     c.transform.trans(10.0, (i + 1) as f64 * 14.0 + 10.0),
-                    g,
+                    canvas.g,
                 )
                 .unwrap();
             }
@@ -101,47 +408,225 @@ fn main() {
 
         if let Some(button) = event.press_args() {
             match button {
+                Button::Keyboard(Key::LCtrl | Key::RCtrl) => ctrl = true,
+                Button::Keyboard(Key::LShift | Key::RShift) => shift = true,
+                Button::Keyboard(Key::S) if ctrl => {
+                    let (category, category_brushes) = &brushes[category_idx];
+                    let settings = session::Settings {
+                        step_millis: Some(step_millis),
+                        category: Some(category.clone()),
+                        brush: Some(category_brushes[brush_idx].0.to_string()),
+                    };
+
+                    session_status = match session::save(session_path, &cgol, &settings) {
+                        Ok(()) => "(saved)".to_string(),
+                        Err(e) => format!("(error: {})", e),
+                    };
+                }
+                Button::Keyboard(Key::O) if ctrl => match session::load(session_path) {
+                    Ok((loaded, settings)) => {
+                        if [cgol.cols(), cgol.rows()] != [loaded.cols(), loaded.rows()] {
+                            renderer.fit(board(&loaded), window_size);
+                            cursor = renderer.pos_to_indices(mouse);
+                        }
+                        cgol = loaded;
+
+                        apply(settings, &mut step_millis, &mut category_idx, &mut brush_idx);
+                        selection = None;
+                        soup_seed = None;
+                        history.clear();
+                        session_status = "(loaded)".to_string();
+                    }
+                    Err(e) => session_status = format!("(error: {})", e),
+                },
                 Button::Keyboard(Key::Space) => running = !running,
-                Button::Keyboard(Key::C) => cgol.clear(),
+                Button::Keyboard(Key::C) => {
+                    cgol.clear();
+                    selection = None;
+                    history.clear();
+                }
                 Button::Keyboard(Key::A) => renderer.show_age = !renderer.show_age,
+                Button::Keyboard(Key::K) => {
+                    gradient_idx = (gradient_idx + 1) % gradients.len();
+                    renderer.palette.ages = gradients[gradient_idx].clone();
+                }
+                Button::Keyboard(Key::T) => {
+                    // Themes keep the current age colors
+                    theme_idx = (theme_idx + 1) % themes.len();
+                    let ages = std::mem::take(&mut renderer.palette.ages);
+                    renderer.palette = Palette { ages, ..themes[theme_idx].1.clone() };
+                }
+                Button::Keyboard(Key::L) => renderer.gridlines = !renderer.gridlines,
+                Button::Keyboard(Key::P) => show_graph = !show_graph,
+                Button::Keyboard(Key::F) => {
+                    // Leaves a margin of a few cells around the pattern
+                    let region = cgol.bounds().map_or(board(&cgol), |[left, top, right, bottom]| {
+                        let margin = 4;
+                        [
+                            left.saturating_sub(margin),
+                            top.saturating_sub(margin),
+                            right - left + 1 + 2 * margin,
+                            bottom - top + 1 + 2 * margin,
+                        ]
+                    });
+                    renderer.fit(region, window_size);
+                    cursor = renderer.pos_to_indices(mouse);
+                }
+                Button::Keyboard(Key::Minus) => {
+                    let size = [cgol.cols().saturating_sub(50), cgol.rows().saturating_sub(50)];
+                    resize_board(&mut cgol, &mut renderer, size);
+                    cursor = renderer.pos_to_indices(mouse);
+                    selection = None;
+                }
+                Button::Keyboard(Key::Equals) => {
+                    let size = [cgol.cols() + 50, cgol.rows() + 50];
+                    resize_board(&mut cgol, &mut renderer, size);
+                    cursor = renderer.pos_to_indices(mouse);
+                    selection = None;
+                }
+                Button::Keyboard(Key::W) => {
+                    let scale = renderer.scale();
+                    resize_board(&mut cgol, &mut renderer, window_size.map(|n| (n / scale) as usize));
+                    renderer.fit(board(&cgol), window_size);
+                    cursor = renderer.pos_to_indices(mouse);
+                    selection = None;
+                }
+                Button::Keyboard(Key::H) => {
+                    renderer.fit(board(&cgol), window_size);
+                    cursor = renderer.pos_to_indices(mouse);
+                }
+                Button::Keyboard(Key::V) => {
+                    if let Some((path, recorder)) = recording.take() {
+                        recording_status = match recorder.finish() {
+                            Ok(()) => format!("off (saved {})", path),
+                            Err(e) => format!("off (error: {})", e),
+                        };
+                    } else {
+                        let path = format!("generation_{}.gif", cgol.generation());
+                        let started = File::create(&path).and_then(|file| {
+                            let mut recorder = GifRecorder::new(BufWriter::new(file)).renderer(renderer.clone());
+                            recorder.record(cgol.cells())?;
+                            Ok(recorder)
+                        });
+
+                        match started {
+                            Ok(recorder) => recording = Some((path, recorder)),
+                            Err(e) => recording_status = format!("off (error: {})", e),
+                        }
+                    }
+                }
                 Button::Keyboard(Key::Up) => step_millis = step_millis_range.clamp(step_millis / 2),
                 Button::Keyboard(Key::Down) => step_millis = step_millis_range.clamp(step_millis * 2),
                 Button::Keyboard(Key::R) => {
-                    use rand::random;
+                    let soup = Soup::new(rand::random()).size(cgol.cols(), cgol.rows());
+                    soup_seed = Some(soup.seed());
                     cgol.clear();
-                    for (col, row) in cgol.cells().indices() {
-                        if random::<bool>() {
-                            cgol.set_cell(col, row, CgolCell::Live(0));
-                        }
-                    }
+                    cgol.put(&soup.generate(), 0, 0);
+                    selection = None;
+                    history.clear();
                 }
-                Button::Keyboard(Key::B) => brush_idx = (brush_idx + 1) % brushes.len(),
+                Button::Keyboard(Key::G) => {
+                    category_idx = (category_idx + 1) % brushes.len();
+                    brush_idx = 0;
+                }
+                Button::Keyboard(Key::B) => brush_idx = (brush_idx + 1) % brushes[category_idx].1.len(),
+                Button::Mouse(MouseButton::Middle) => panning = true,
+                Button::Mouse(MouseButton::Left) if shift => panning = true,
                 Button::Mouse(MouseButton::Left) => {
-                    let brush = brushes[brush_idx].1;
-                    let col = cursor[0] as isize - brush.cols() as isize / 2;
-                    let row = cursor[1] as isize - brush.rows() as isize / 2;
-
-                    if brush_idx == 0 {
-                        cgol.with_cell_mut(col as usize, row as usize, |cell| cell.toggle());
-                    } else {
-                        cgol.put(brush, col, row);
-                    }
+                    paint(&mut cgol, brushes[category_idx].1[brush_idx].1, category_idx == 0, cursor)
+                }
+                Button::Mouse(MouseButton::Right) => {
+                    selection = on_board(&cgol, cursor)
+                        .and_then(|[col, row]| cgol.cells().component_at(col, row, Connectivity::Distance2))
+                        .map(|object| {
+                            let mut code = apgcode::encode(&object.cells, 60).unwrap_or_else(|| "unknown".to_string());
+                            if let Some(velocity) = find_period(&object.cells, 60).and_then(|p| p.velocity()) {
+                                code = format!("{} ({})", code, velocity);
+                            }
+                            (object, code)
+                        });
                 }
                 _ => (),
             }
         }
 
+        // Any button may have edited the board
+        if event.press_args().is_some() {
+            mipmap = None;
+        }
+
+        match event.release_args() {
+            Some(Button::Keyboard(Key::LCtrl | Key::RCtrl)) => ctrl = false,
+            Some(Button::Keyboard(Key::LShift | Key::RShift)) => shift = false,
+            Some(Button::Mouse(MouseButton::Left | MouseButton::Middle)) => panning = false,
+            _ => (),
+        }
+
+        if let Some(args) = event.resize_args() {
+            window_size = args.window_size;
+        }
+
         if let Some(pos) = event.mouse_cursor_args() {
+            if panning {
+                renderer.pan([pos[0] - mouse[0], pos[1] - mouse[1]]);
+            }
+            mouse = pos;
             cursor = renderer.pos_to_indices(pos);
         }
 
+        if let Some([_, scroll]) = event.mouse_scroll_args() {
+            renderer.zoom_at(1.25f64.powf(scroll), mouse);
+            cursor = renderer.pos_to_indices(mouse);
+        }
+
         if running {
             let now = Instant::now();
             if now - last_update >= Duration::from_millis(step_millis) {
                 last_update = now;
                 cgol.step();
-                generation += 1;
+                selection = None;
+                mipmap = None;
+
+                if history.len() == graph_length {
+                    history.pop_front();
+                }
+                history.push_back(Metrics::of(&cgol));
+
+                if let Some((path, recorder)) = &mut recording {
+                    if let Err(e) = recorder.record(cgol.cells()) {
+                        recording_status = format!("off (error: {}: {})", path, e);
+                        recording = None;
+                    }
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paint_outside() {
+        let mut cgol = Automaton::<Cgol>::new([4, 3]);
+        let toggle = Grid::new(1, 1);
+
+        // Left of, above, right of & below the board, including the cell that
+        // would wrap around to the start of the next row
+        let outside = [[-1, 0], [0, -1], [-1, -1], [4, 0], [9, 1], [0, 3], [4, 3]];
+        for &cursor in &outside {
+            paint(&mut cgol, &toggle, true, cursor);
+            paint(&mut cgol, &cgol::patterns::BLOCK_2, false, cursor);
+        }
+        assert_eq!(cgol.stats().population, 0);
+
+        // Brushes are cut off at the edges
+        paint(&mut cgol, &cgol::patterns::BLOCK_2, false, [0, 0]);
+        assert_eq!(cgol.stats().population, 1);
+        assert_eq!(*cgol.cell(0, 0), CgolCell::Live(0));
+
+        paint(&mut cgol, &toggle, true, [3, 2]);
+        assert_eq!(*cgol.cell(3, 2), CgolCell::Live(0));
+    }
+}
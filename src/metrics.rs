@@ -0,0 +1,166 @@
+use crate::automaton::{Automaton, Ruleset};
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
+
+/// Output format of recorded metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Comma separated values with a header line
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("unknown metrics format: {}", s)),
+        }
+    }
+}
+
+/// Snapshot of an automaton's statistics at some generation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    pub generation: u64,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+    /// Bounding box of live cells as `[left, top, right, bottom]`
+    pub bounds: Option<[usize; 4]>,
+    pub mean_age: Option<f64>,
+}
+
+impl Metrics {
+    pub fn of<R: Ruleset>(automaton: &Automaton<R>) -> Self {
+        let stats = automaton.stats();
+
+        Self {
+            generation: automaton.generation(),
+            population: stats.population,
+            births: stats.births,
+            deaths: stats.deaths,
+            bounds: automaton.bounds(),
+            mean_age: stats.mean_age(),
+        }
+    }
+
+    /// Returns the width and height of the bounding box of live cells
+    pub fn size(&self) -> (usize, usize) {
+        self.bounds.map_or((0, 0), |[left, top, right, bottom]| (right - left + 1, bottom - top + 1))
+    }
+}
+
+const FIELDS: &[&str] = &["generation", "population", "births", "deaths", "width", "height", "mean_age"];
+
+/// Writes metrics of consecutive generations as a time series
+pub struct Recorder<W: Write> {
+    writer: W,
+    format: Format,
+    started: bool,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(writer: W, format: Format) -> Self {
+        Self {
+            writer,
+            format,
+            started: false,
+        }
+    }
+
+    /// Writes the current metrics of the automaton, meant to be called after
+    /// each step
+    pub fn record<R: Ruleset>(&mut self, automaton: &Automaton<R>) -> io::Result<()> {
+        self.write(&Metrics::of(automaton))
+    }
+
+    pub fn write(&mut self, metrics: &Metrics) -> io::Result<()> {
+        let (width, height) = metrics.size();
+        let values = [
+            metrics.generation.to_string(),
+            metrics.population.to_string(),
+            metrics.births.to_string(),
+            metrics.deaths.to_string(),
+            width.to_string(),
+            height.to_string(),
+            metrics.mean_age.map_or(String::new(), |age| age.to_string()),
+        ];
+
+        match self.format {
+            Format::Csv => {
+                if !self.started {
+                    writeln!(self.writer, "{}", FIELDS.join(","))?;
+                }
+                writeln!(self.writer, "{}", values.join(","))?;
+            }
+            Format::JsonLines => {
+                let fields = FIELDS
+                    .iter()
+                    .zip(&values)
+                    .map(|(name, value)| {
+                        format!("\"{}\":{}", name, if value.is_empty() { "null" } else { value })
+                    })
+                    .collect::<Vec<_>>();
+
+                writeln!(self.writer, "{{{}}}", fields.join(","))?;
+            }
+        }
+
+        self.started = true;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{cgol::patterns, Cgol};
+
+    fn record(format: Format) -> String {
+        let mut cgol = Automaton::<Cgol>::new([5, 5]);
+        cgol.put(&patterns::BLINKER, 2, 1);
+
+        let mut recorder = Recorder::new(Vec::new(), format);
+        recorder.record(&cgol).unwrap();
+        cgol.step();
+        recorder.record(&cgol).unwrap();
+        cgol.clear();
+        recorder.record(&cgol).unwrap();
+
+        String::from_utf8(recorder.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(record(Format::Csv), concat!(
+            "generation,population,births,deaths,width,height,mean_age\n",
+            "0,3,0,0,1,3,0\n",
+            "1,3,2,2,3,1,0.3333333333333333\n",
+            "0,0,0,0,0,0,\n",
+        ));
+    }
+
+    #[test]
+    fn json_lines() {
+        assert_eq!(record(Format::JsonLines), concat!(
+            r#"{"generation":0,"population":3,"births":0,"deaths":0,"width":1,"height":3,"mean_age":0}"#, "\n",
+            r#"{"generation":1,"population":3,"births":2,"deaths":2,"width":3,"height":1,"mean_age":0.3333333333333333}"#, "\n",
+            r#"{"generation":0,"population":0,"births":0,"deaths":0,"width":0,"height":0,"mean_age":null}"#, "\n",
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    automaton::Automaton,
+    cgol::{Cgol, CgolCell},
+    grid::Grid,
+};
+use std::fmt;
+
+/// Periodic behavior of a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    /// Number of generations after which the pattern repeats
+    pub period: usize,
+    /// Displacement of the pattern after one period, nonzero for spaceships
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Period {
+    pub const fn is_still(&self) -> bool {
+        self.period == 1 && !self.is_moving()
+    }
+
+    pub const fn is_moving(&self) -> bool {
+        self.dx != 0 || self.dy != 0
+    }
+
+    /// Returns the velocity of the pattern if it moves
+    pub fn velocity(&self) -> Option<Velocity> {
+        if self.is_moving() {
+            Some(Velocity { dx: self.dx, dy: self.dy, period: self.period })
+        } else {
+            None
+        }
+    }
+}
+
+/// Direction class of a spaceship
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Moving along a row or a column
+    Orthogonal,
+    /// Moving along a 45° diagonal
+    Diagonal,
+    /// Moving in any other direction
+    Oblique,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Orthogonal => "orthogonal",
+            Self::Diagonal => "diagonal",
+            Self::Oblique => "oblique",
+        })
+    }
+}
+
+/// Displacement of a spaceship per period
+///
+/// Displays in the usual notation, e.g. `c/4 diagonal` for the glider,
+/// `c/2 orthogonal` for the LWSS or `(2,1)c/6 oblique`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Velocity {
+    pub dx: isize,
+    pub dy: isize,
+    pub period: usize,
+}
+
+impl Velocity {
+    pub fn direction(&self) -> Direction {
+        if self.dx == 0 || self.dy == 0 {
+            Direction::Orthogonal
+        } else if self.dx.abs() == self.dy.abs() {
+            Direction::Diagonal
+        } else {
+            Direction::Oblique
+        }
+    }
+
+    /// Returns the speed along each axis in cells per generation
+    pub fn speed(&self) -> (f64, f64) {
+        (self.dx as f64 / self.period as f64, self.dy as f64 / self.period as f64)
+    }
+}
+
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = self.direction();
+
+        if direction == Direction::Oblique {
+            let (a, b) = (self.dx.unsigned_abs(), self.dy.unsigned_abs());
+            return write!(f, "({},{})c/{} {}", a.max(b), a.min(b), self.period, direction);
+        }
+
+        // Orthogonal and diagonal speeds are given by the displacement along
+        // a single axis, reduced like a fraction
+        let distance = self.dx.unsigned_abs().max(self.dy.unsigned_abs());
+        let d = gcd(distance, self.period);
+        let (distance, period) = (distance / d, self.period / d);
+
+        match (distance, period) {
+            (1, 1) => write!(f, "c {}", direction),
+            (1, p) => write!(f, "c/{} {}", p, direction),
+            (n, 1) => write!(f, "{}c {}", n, direction),
+            (n, p) => write!(f, "{}c/{} {}", n, p, direction),
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Returns the positions of live cells relative to their bounding box, along with
+/// the top left corner of the bounding box
+pub fn live_cells(grid: &Grid<CgolCell>) -> (Vec<(usize, usize)>, (usize, usize)) {
+    let live = grid
+        .into_iter()
+        .filter(|(_, cell)| matches!(cell, CgolCell::Live(_)))
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>();
+
+    let left = live.iter().map(|&(col, _)| col).min().unwrap_or(0);
+    let top = live.iter().map(|&(_, row)| row).min().unwrap_or(0);
+
+    let shape = live.into_iter().map(|(col, row)| (col - left, row - top)).collect();
+    (shape, (left, top))
+}
+
+/// Runs the pattern for up to `max_period` generations and returns its period
+/// if it returns to its initial shape, possibly displaced
+///
+/// Returns `None` for empty patterns and for patterns that are not periodic
+/// starting from the given phase.
+pub fn find_period(pattern: &Grid<CgolCell>, max_period: usize) -> Option<Period> {
+    // Nothing moves faster than one cell per generation, so this much padding
+    // keeps the pattern from ever reaching the edge of the board
+    let pad = max_period + 2;
+    let mut cgol = Automaton::<Cgol>::new([pattern.cols() + 2 * pad, pattern.rows() + 2 * pad]);
+    cgol.put(pattern, pad as isize, pad as isize);
+
+    let (initial, (left, top)) = live_cells(cgol.cells());
+    if initial.is_empty() {
+        return None;
+    }
+
+    for period in 1..=max_period {
+        cgol.step();
+
+        let (shape, (l, t)) = live_cells(cgol.cells());
+        if shape == initial {
+            return Some(Period {
+                period,
+                dx: l as isize - left as isize,
+                dy: t as isize - top as isize,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns;
+
+    #[test]
+    fn still_life() {
+        let p = find_period(&patterns::BEEHIVE, 8).unwrap();
+        assert!(p.is_still());
+    }
+
+    #[test]
+    fn oscillator() {
+        assert_eq!(find_period(&patterns::BLINKER, 8), Some(Period { period: 2, dx: 0, dy: 0 }));
+        assert_eq!(find_period(&patterns::PULSAR, 8), Some(Period { period: 3, dx: 0, dy: 0 }));
+    }
+
+    #[test]
+    fn spaceship() {
+        assert_eq!(find_period(&patterns::GLIDER, 8), Some(Period { period: 4, dx: 1, dy: 1 }));
+        assert_eq!(find_period(&patterns::LWSS, 8), Some(Period { period: 4, dx: 2, dy: 0 }));
+    }
+
+    #[test]
+    fn velocity() {
+        let velocity = |pattern| find_period(pattern, 12).and_then(|p| p.velocity());
+
+        let glider = velocity(&patterns::GLIDER).unwrap();
+        assert_eq!(glider.direction(), Direction::Diagonal);
+        assert_eq!(glider.speed(), (0.25, 0.25));
+        assert_eq!(glider.to_string(), "c/4 diagonal");
+
+        for ship in &[&*patterns::LWSS, &*patterns::MWSS] {
+            let v = velocity(ship).unwrap();
+            assert_eq!(v.direction(), Direction::Orthogonal);
+            assert_eq!(v.speed(), (0.5, 0.0));
+            assert_eq!(v.to_string(), "c/2 orthogonal");
+        }
+
+        assert_eq!(velocity(&patterns::SCHICK_ENGINE).unwrap().to_string(), "c/2 orthogonal");
+        assert_eq!(velocity(&patterns::PULSAR), None);
+
+        let oblique = Velocity { dx: -2, dy: 1, period: 6 };
+        assert_eq!(oblique.to_string(), "(2,1)c/6 oblique");
+        assert_eq!(Velocity { dx: 0, dy: 2, period: 5 }.to_string(), "2c/5 orthogonal");
+    }
+
+    #[test]
+    fn aperiodic() {
+        assert_eq!(find_period(&patterns::BLOCK_1, 8), None);
+    }
+}
@@ -0,0 +1,996 @@
+//! Drawing of automatons independent of the graphics backend
+//!
+//! `Renderer` draws onto any `Canvas`, either a window through the GUI's backend
+//! or a `Framebuffer` in memory.
+
+use crate::{
+    cgol::CgolCell,
+    grid::{Component, Grid},
+    metrics::Metrics,
+};
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    ops::Range,
+    str::FromStr,
+};
+
+/// RGBA color with components between 0 and 1
+pub type Color = [f32; 4];
+
+/// Parses an opaque color written as `#rrggbb`
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    let hex = s
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())
+        .ok_or_else(|| format!("invalid color: {}", s))?;
+
+    let component = |i: usize| {
+        u8::from_str_radix(&hex[i..i + 2], 16)
+            .map(|c| c as f32 / 255.0)
+            .map_err(|_| format!("invalid color: {}", s))
+    };
+
+    Ok([component(0)?, component(2)?, component(4)?, 1.0])
+}
+
+/// Colors of live cells by their age
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Gradient {
+    /// The color of live cells faded towards the background
+    #[default]
+    Fade,
+    /// White hot newborn cells cooling down to dark red
+    Heat,
+    /// The perceptually uniform viridis map, from yellow to purple
+    Viridis,
+    /// Cells born in the last step in the color, older cells in the color of
+    /// live cells
+    Newborn(Color),
+    /// Colors evenly spaced from newborn to old cells
+    Custom(Vec<Color>),
+}
+
+impl Gradient {
+    const HEAT: [Color; 5] = [
+        [1.0, 1.0, 0.8, 1.0],
+        [1.0, 0.9, 0.0, 1.0],
+        [1.0, 0.5, 0.0, 1.0],
+        [0.8, 0.1, 0.0, 1.0],
+        [0.35, 0.0, 0.0, 1.0],
+    ];
+
+    const VIRIDIS: [Color; 5] = [
+        [0.992, 0.906, 0.145, 1.0],
+        [0.369, 0.788, 0.384, 1.0],
+        [0.129, 0.569, 0.549, 1.0],
+        [0.231, 0.322, 0.545, 1.0],
+        [0.267, 0.004, 0.329, 1.0],
+    ];
+
+    /// Returns the gradients that can be selected by name, with the default color
+    /// for newborn cells
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::Fade, Self::Heat, Self::Viridis, Self::Newborn([0.3, 1.0, 0.3, 1.0])]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fade => "fade",
+            Self::Heat => "heat",
+            Self::Viridis => "viridis",
+            Self::Newborn(_) => "newborn",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+/// Parses the name of a gradient, `newborn:#rrggbb` or custom colors as
+/// `#newborn,...,#old`
+impl FromStr for Gradient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(gradient) = Self::builtin().into_iter().find(|g| g.name() == s) {
+            return Ok(gradient);
+        }
+        if let Some(color) = s.strip_prefix("newborn:") {
+            return parse_color(color).map(Self::Newborn);
+        }
+
+        let colors = s.split(',').map(parse_color).collect::<Result<Vec<_>, _>>();
+        match colors {
+            Ok(colors) if colors.len() >= 2 => Ok(Self::Custom(colors)),
+            _ => Err(format!("invalid gradient: {}", s)),
+        }
+    }
+}
+
+/// Returns the color at `t` between 0 and 1 along evenly spaced colors
+fn sample(colors: &[Color], t: f32) -> Color {
+    let position = t.clamp(0.0, 1.0) * (colors.len() - 1) as f32;
+    let i = (position.floor() as usize).min(colors.len() - 2);
+    let (a, b, t) = (colors[i], colors[i + 1], position - i as f32);
+    [0, 1, 2, 3].map(|c| a[c] * (1.0 - t) + b[c] * t)
+}
+
+/// Colors used by `Renderer`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub background: Color,
+    /// Color of live cells if ages aren't shown
+    pub live: Color,
+    pub gridlines: Color,
+    /// Color drawn over the cells the brush would place
+    pub brush: Color,
+    /// Color of the outline around the selected object
+    pub selection: Color,
+    pub text: Color,
+    /// Colors of the graph's panel & its outline
+    pub graph_background: Color,
+    pub graph_border: Color,
+    /// Colors of the population, births & deaths plotted in the graph
+    pub graph_series: [Color; 3],
+    /// Colors of live cells if ages are shown
+    pub ages: Gradient,
+}
+
+impl Palette {
+    pub const DARK: Self = Self {
+        background: [0.0, 0.0, 0.0, 1.0],
+        live: [1.0, 1.0, 1.0, 1.0],
+        gridlines: [0.2, 0.2, 0.2, 1.0],
+        brush: [1.0, 1.0, 0.0, 0.3],
+        selection: [1.0, 0.5, 0.0, 1.0],
+        text: [0.6, 0.7, 1.0, 1.0],
+        graph_background: [0.0, 0.0, 0.0, 0.7],
+        graph_border: [0.6, 0.7, 1.0, 0.5],
+        graph_series: [[1.0, 1.0, 1.0, 1.0], [0.3, 1.0, 0.3, 1.0], [1.0, 0.3, 0.3, 1.0]],
+        ages: Gradient::Fade,
+    };
+
+    pub const LIGHT: Self = Self {
+        background: [1.0, 1.0, 1.0, 1.0],
+        live: [0.0, 0.0, 0.0, 1.0],
+        gridlines: [0.8, 0.8, 0.8, 1.0],
+        brush: [0.0, 0.4, 1.0, 0.3],
+        selection: [1.0, 0.4, 0.0, 1.0],
+        text: [0.1, 0.2, 0.5, 1.0],
+        graph_background: [1.0, 1.0, 1.0, 0.8],
+        graph_border: [0.1, 0.2, 0.5, 0.5],
+        graph_series: [[0.0, 0.0, 0.0, 1.0], [0.0, 0.6, 0.0, 1.0], [0.8, 0.0, 0.0, 1.0]],
+        ages: Gradient::Fade,
+    };
+
+    /// Returns the color of a live cell of the age along the gradient, with cells
+    /// reaching its end at age 9
+    pub fn aged(&self, age: u8) -> Color {
+        let lightness = 0.1f32.max(1.0 / age.saturating_add(1) as f32);
+        let t = (1.0 - lightness) / 0.9;
+
+        match &self.ages {
+            Gradient::Fade => self.fade(lightness),
+            Gradient::Heat => sample(&Gradient::HEAT, t),
+            Gradient::Viridis => sample(&Gradient::VIRIDIS, t),
+            Gradient::Newborn(newborn) if age == 0 => *newborn,
+            Gradient::Newborn(_) => self.live,
+            Gradient::Custom(colors) => sample(colors, t),
+        }
+    }
+
+    /// Returns the color between the background at 0 and live cells at 1
+    pub fn fade(&self, amount: f32) -> Color {
+        let (live, background) = (self.live, self.background);
+        let fade = |i: usize| background[i] + (live[i] - background[i]) * amount;
+        [fade(0), fade(1), fade(2), live[3]]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+/// Parses `dark`, `light` or custom colors as `#background,#live,#gridlines`,
+/// with the other colors of the light palette for light backgrounds and of the
+/// dark one otherwise
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dark" => Ok(Self::DARK),
+            "light" => Ok(Self::LIGHT),
+            _ => match s.split(',').collect::<Vec<_>>()[..] {
+                [background, live, gridlines] => {
+                    let background = parse_color(background)?;
+                    let lightness = 0.299 * background[0] + 0.587 * background[1] + 0.114 * background[2];
+                    let base = if lightness > 0.5 { Self::LIGHT } else { Self::DARK };
+
+                    Ok(Self {
+                        background,
+                        live: parse_color(live)?,
+                        gridlines: parse_color(gridlines)?,
+                        ..base
+                    })
+                }
+                _ => Err(format!("invalid palette: {}", s)),
+            },
+        }
+    }
+}
+
+/// Surface supporting the primitives used by `Renderer`
+///
+/// Rectangles are given as `[x, y, width, height]` in pixels.
+pub trait Canvas {
+    /// Returns the width & height of the canvas, outside of which nothing is
+    /// drawn
+    fn size(&self) -> [f64; 2];
+
+    /// Fills the whole canvas with the color
+    fn clear(&mut self, color: Color);
+
+    fn rectangle(&mut self, color: Color, rect: [f64; 4]);
+
+    /// Draws the outline of a rectangle with lines of the given half-width
+    fn border(&mut self, color: Color, radius: f64, rect: [f64; 4]);
+
+    /// Draws a line of the given half-width
+    fn line(&mut self, color: Color, radius: f64, from: [f64; 2], to: [f64; 2]);
+}
+
+/// RGBA image in memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// Allocates a transparent image
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width * height * 4],
+        }
+    }
+
+    pub const fn width(&self) -> usize { self.width }
+    pub const fn height(&self) -> usize { self.height }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+        [self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]]
+    }
+
+    /// Returns the pixels row by row, 4 bytes each
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Reads a PNG file of any color type, converting it to RGBA
+    pub fn read_png(reader: impl Read) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::EXPAND);
+
+        let (info, mut reader) = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+
+        let (color_type, bit_depth) = reader.output_color_type();
+        let channels = color_type.samples();
+        // Only the most significant byte of 16 bit samples is kept
+        let bytes = if bit_depth == png::BitDepth::Sixteen { 2 } else { 1 };
+
+        let (width, height) = (info.width as usize, info.height as usize);
+        let mut image = Self::new(width, height);
+
+        for y in 0..height {
+            let line = &buf[y * info.line_size..];
+            for x in 0..width {
+                let sample = |i: usize| line[(x * channels + i) * bytes];
+                let pixel = match channels {
+                    1 => [sample(0), sample(0), sample(0), 255],
+                    2 => [sample(0), sample(0), sample(0), sample(1)],
+                    3 => [sample(0), sample(1), sample(2), 255],
+                    _ => [sample(0), sample(1), sample(2), sample(3)],
+                };
+
+                let i = (y * width + x) * 4;
+                image.data[i..i + 4].copy_from_slice(&pixel);
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Writes the image as a PNG file
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        Ok(())
+    }
+
+    /// Blends the color over the pixel if it lies within the image
+    pub fn blend(&mut self, x: isize, y: isize, color: Color) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+
+        let i = (y as usize * self.width + x as usize) * 4;
+        let pixel = &mut self.data[i..i + 4];
+        let alpha = color[3].clamp(0.0, 1.0);
+        let dst_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+
+        for c in 0..3 {
+            let dst = pixel[c] as f32 / 255.0;
+            let src = color[c].clamp(0.0, 1.0);
+            let out = if out_alpha > 0.0 {
+                (src * alpha + dst * dst_alpha * (1.0 - alpha)) / out_alpha
+            } else {
+                0.0
+            };
+            pixel[c] = (out * 255.0).round() as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+impl Canvas for Framebuffer {
+    fn size(&self) -> [f64; 2] {
+        [self.width as f64, self.height as f64]
+    }
+
+    fn clear(&mut self, color: Color) {
+        let pixel = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        for chunk in self.data.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    /// Fills the pixels whose centers lie within the rectangle
+    fn rectangle(&mut self, color: Color, [x, y, width, height]: [f64; 4]) {
+        let (left, right) = ((x - 0.5).ceil() as isize, (x + width - 0.5).ceil() as isize);
+        let (top, bottom) = ((y - 0.5).ceil() as isize, (y + height - 0.5).ceil() as isize);
+
+        let (left, top) = (left.max(0), top.max(0));
+        let right = right.min(self.width as isize);
+        let bottom = bottom.min(self.height as isize);
+
+        for py in top..bottom {
+            for px in left..right {
+                self.blend(px, py, color);
+            }
+        }
+    }
+
+    fn border(&mut self, color: Color, radius: f64, [x, y, width, height]: [f64; 4]) {
+        let d = 2.0 * radius;
+
+        // Centered on the edges like piston's borders, without overlapping corners
+        self.rectangle(color, [x - radius, y - radius, width + d, d]);
+        self.rectangle(color, [x - radius, y + height - radius, width + d, d]);
+        self.rectangle(color, [x - radius, y + radius, d, height - d]);
+        self.rectangle(color, [x + width - radius, y + radius, d, height - d]);
+    }
+
+    fn line(&mut self, color: Color, radius: f64, [x0, y0]: [f64; 2], [x1, y1]: [f64; 2]) {
+        // Pixels are visited once each so translucent lines blend evenly
+        let size = (2.0 * radius).max(1.0);
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as usize;
+        let mut last = None;
+
+        for i in 0..=steps {
+            let t = i as f64 / steps as f64;
+            let (x, y) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+            let pixel = ((x - size / 2.0).round() as isize, (y - size / 2.0).round() as isize);
+
+            if last != Some(pixel) {
+                for dy in 0..size.round() as isize {
+                    for dx in 0..size.round() as isize {
+                        self.blend(pixel.0 + dx, pixel.1 + dy, color);
+                    }
+                }
+                last = Some(pixel);
+            }
+        }
+    }
+}
+
+/// Calls `draw` with the start, end & color of each run of consecutive indices
+/// of the same color
+fn spans(range: Range<usize>, color: impl Fn(usize) -> Option<Color>, mut draw: impl FnMut(usize, usize, Color)) {
+    let mut span: Option<(usize, Color)> = None;
+
+    for i in range.clone() {
+        let current = color(i);
+        if let Some((start, color)) = span {
+            if current != Some(color) {
+                draw(start, i, color);
+                span = None;
+            }
+        }
+        if span.is_none() {
+            span = current.map(|color| (i, color));
+        }
+    }
+    if let Some((start, color)) = span {
+        draw(start, range.end, color);
+    }
+}
+
+/// Numbers of live cells in square blocks of a grid, with each level halving
+/// the resolution of the one below
+#[derive(Debug, Clone)]
+pub struct Mipmap {
+    dims: [usize; 2],
+    /// Live cells in blocks of `2^(i + 1)` cells per side
+    levels: Vec<Grid<u32>>,
+}
+
+impl Mipmap {
+    /// Counts live cells at all levels down to a single block covering the whole
+    /// grid
+    pub fn new(grid: &Grid<CgolCell>) -> Self {
+        let live = |col: usize, row: usize| matches!(grid[(col, row)], CgolCell::Live(_)) as u32;
+
+        let mut levels: Vec<Grid<u32>> = Vec::new();
+        let mut size = [grid.cols(), grid.rows()];
+        while size[0] > 1 || size[1] > 1 {
+            let level = match levels.last() {
+                None => halve(size, live),
+                Some(below) => halve(size, |col, row| below[(col, row)]),
+            };
+            size = [level.cols(), level.rows()];
+            levels.push(level);
+        }
+
+        Self { dims: [grid.cols(), grid.rows()], levels }
+    }
+
+    /// Returns the numbers of live cells in blocks of `block` cells per side,
+    /// which has to be a power of two of at least 2
+    pub fn counts(&self, block: usize) -> Option<&Grid<u32>> {
+        if !block.is_power_of_two() || block < 2 {
+            return None;
+        }
+        self.levels.get(block.trailing_zeros() as usize - 1)
+    }
+
+    /// Returns the fraction of live cells in a block, out of the cells of the
+    /// block within the grid
+    pub fn density(&self, block: usize, col: usize, row: usize) -> f32 {
+        let count = self.counts(block).map_or(0, |counts| counts[(col, row)]);
+        let [cols, rows] = self.dims;
+        let area = block.min(cols - col * block) * block.min(rows - row * block);
+        count as f32 / area as f32
+    }
+}
+
+/// Sums each square of 2 by 2 values of a grid of the size
+fn halve([cols, rows]: [usize; 2], value: impl Fn(usize, usize) -> u32) -> Grid<u32> {
+    let mut halved = Grid::new(cols.div_ceil(2), rows.div_ceil(2));
+
+    for ((col, row), sum) in &mut halved {
+        for (c, r) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let (c, r) = (col * 2 + c, row * 2 + r);
+            if c < cols && r < rows {
+                *sum += value(c, r);
+            }
+        }
+    }
+
+    halved
+}
+
+/// View of the board through the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// Position of the window's top left corner on the board, in cells
+    pub origin: [f64; 2],
+    /// Factor by which cells are scaled from `Renderer::cell_size`
+    pub zoom: f64,
+}
+
+impl Camera {
+    pub const MIN_ZOOM: f64 = 1.0 / 16.0;
+    pub const MAX_ZOOM: f64 = 16.0;
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self { origin: [0.0, 0.0], zoom: 1.0 }
+    }
+}
+
+/// Draws cells, brushes & overlays
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub cell_size: f64,
+    pub show_age: bool,
+    pub gridlines: bool,
+    pub palette: Palette,
+    pub camera: Camera,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            cell_size: 4.0,
+            show_age: true,
+            gridlines: false,
+            palette: Palette::default(),
+            camera: Camera::default(),
+        }
+    }
+}
+
+impl Renderer {
+    /// Draws the grid onto a new framebuffer of the grid's size, ignoring the
+    /// camera
+    pub fn render(&self, grid: &Grid<CgolCell>) -> Framebuffer {
+        let renderer = Self { camera: Camera::default(), ..self.clone() };
+        let [width, height] = renderer.window_size([grid.cols(), grid.rows()]);
+        let mut framebuffer = Framebuffer::new(width.ceil() as usize, height.ceil() as usize);
+
+        framebuffer.clear(self.palette.background);
+        renderer.draw_grid(grid, &mut framebuffer);
+        framebuffer
+    }
+
+    /// Returns the size of a cell on screen
+    pub fn scale(&self) -> f64 {
+        self.cell_size * self.camera.zoom
+    }
+
+    /// Returns the `[x, y, width, height]` rectangle covering the cells on screen
+    fn cells_rect(&self, [col, row]: [f64; 2], [cols, rows]: [f64; 2]) -> [f64; 4] {
+        let scale = self.scale();
+        let [x, y] = self.camera.origin;
+        [(col - x) * scale, (row - y) * scale, cols * scale, rows * scale]
+    }
+
+    /// Returns the ranges of columns & rows of blocks of cells at least partly
+    /// within the canvas
+    fn visible(&self, canvas: &impl Canvas, block: usize, [cols, rows]: [usize; 2]) -> [Range<usize>; 2] {
+        let [width, height] = canvas.size();
+        let [x, y] = self.camera.origin;
+        let cells = [width / self.scale(), height / self.scale()];
+
+        let range = |origin: f64, cells: f64, count: usize| {
+            let blocks = count.div_ceil(block);
+            let start = (origin / block as f64).floor().clamp(0.0, blocks as f64) as usize;
+            let end = ((origin + cells) / block as f64).ceil().clamp(0.0, blocks as f64) as usize;
+            start..end.max(start)
+        };
+        [range(x, cells[0], cols), range(y, cells[1], rows)]
+    }
+
+    /// Draws live cells and the gridlines if enabled, without clearing the canvas
+    ///
+    /// Only cells within the canvas are drawn, with runs of cells of the same
+    /// color in a row drawn as one rectangle. When cells are smaller than a pixel,
+    /// blocks of cells are drawn instead in a color showing the density of live
+    /// cells within them.
+    pub fn draw_grid(&self, grid: &Grid<CgolCell>, canvas: &mut impl Canvas) {
+        let dims = [grid.cols(), grid.rows()];
+
+        if self.scale() < 1.0 {
+            self.draw_mipmap(&Mipmap::new(grid), canvas);
+        } else {
+            let [cols, rows] = self.visible(canvas, 1, dims);
+            for row in rows {
+                let color = |col: usize| match grid[(col, row)] {
+                    CgolCell::Live(age) if self.show_age => Some(self.palette.aged(age)),
+                    CgolCell::Live(_) => Some(self.palette.live),
+                    CgolCell::Dead => None,
+                };
+                spans(cols.clone(), color, |start, end, color| {
+                    canvas.rectangle(color, self.cells_rect([start as f64, row as f64], [(end - start) as f64, 1.0]));
+                });
+            }
+        }
+
+        // Gridlines would cover the cells when they are this small
+        if self.gridlines && self.scale() >= 2.0 {
+            self.draw_gridlines(dims, canvas);
+        }
+    }
+
+    /// Draws the smallest blocks of cells which are at least a pixel wide, faded
+    /// from the background towards the color of live cells by their density
+    ///
+    /// This is what `draw_grid` draws when cells are smaller than a pixel, without
+    /// gridlines, for callers that keep the mipmap between frames.
+    pub fn draw_mipmap(&self, mipmap: &Mipmap, canvas: &mut impl Canvas) {
+        let [cols, rows] = mipmap.dims;
+        // Past a single block covering the whole grid, including at a scale of 0
+        let largest = cols.max(rows).next_power_of_two().max(2);
+        let cells_per_pixel = (1.0 / self.scale()).ceil();
+        let block = if cells_per_pixel < largest as f64 {
+            (cells_per_pixel as usize).max(2).next_power_of_two()
+        } else {
+            largest
+        };
+        let [block_cols, block_rows] = self.visible(canvas, block, [cols, rows]);
+
+        for row in block_rows {
+            let top = row * block;
+            let height = block.min(rows - top);
+
+            // Densities are rounded so that runs of similar blocks can be merged
+            let color = |col: usize| {
+                let density = (mipmap.density(block, col, row) * 32.0).round() / 32.0;
+                Some(self.palette.fade(density)).filter(|_| density > 0.0)
+            };
+            spans(block_cols.clone(), color, |start, end, color| {
+                let (left, right) = (start * block, (end * block).min(cols));
+                canvas.rectangle(
+                    color,
+                    self.cells_rect([left as f64, top as f64], [(right - left) as f64, height as f64]),
+                );
+            });
+        }
+    }
+
+    /// Draws a line between each pair of adjacent cells, 1 pixel wide and to the
+    /// left of & above each cell
+    fn draw_gridlines(&self, [cols, rows]: [usize; 2], canvas: &mut impl Canvas) {
+        let [left, top, width, height] = self.cells_rect([0.0, 0.0], [cols as f64, rows as f64]);
+
+        for col in 1..cols {
+            let [x, ..] = self.cells_rect([col as f64, 0.0], [0.0, 0.0]);
+            canvas.rectangle(self.palette.gridlines, [x - 1.0, top, 1.0, height]);
+        }
+        for row in 1..rows {
+            let [_, y, ..] = self.cells_rect([0.0, row as f64], [0.0, 0.0]);
+            canvas.rectangle(self.palette.gridlines, [left, y - 1.0, width, 1.0]);
+        }
+    }
+
+    /// Outlines the board, whose edges may be within the window when zoomed out
+    pub fn draw_bounds(&self, [cols, rows]: [usize; 2], canvas: &mut impl Canvas) {
+        let [x, y, width, height] = self.cells_rect([0.0, 0.0], [cols as f64, rows as f64]);
+        canvas.border(self.palette.gridlines, 0.5, [x - 0.5, y - 0.5, width + 1.0, height + 1.0]);
+    }
+
+    pub fn draw_brush(&self, brush: &Grid<CgolCell>, cursor: [isize; 2], canvas: &mut impl Canvas) {
+        for ((col, row), cell) in brush {
+            if let &CgolCell::Live(_) = cell {
+                let col = cursor[0] + col as isize - brush.cols() as isize / 2;
+                let row = cursor[1] + row as isize - brush.rows() as isize / 2;
+
+                canvas.rectangle(self.palette.brush, self.cells_rect([col as f64, row as f64], [1.0, 1.0]));
+            }
+        }
+    }
+
+    pub fn draw_selection(&self, selection: &Component<CgolCell>, canvas: &mut impl Canvas) {
+        let [x, y, width, height] = self.cells_rect(
+            [selection.left as f64, selection.top as f64],
+            [selection.cells.cols() as f64, selection.cells.rows() as f64],
+        );
+        canvas.border(self.palette.selection, 0.5, [x - 1.0, y - 1.0, width + 2.0, height + 2.0]);
+    }
+
+    /// Plots population, births and deaths over the recorded generations within
+    /// the given `[x, y, width, height]` area
+    pub fn draw_graph(&self, history: &VecDeque<Metrics>, area: [f64; 4], canvas: &mut impl Canvas) {
+        let [x, y, width, height] = area;
+        canvas.rectangle(self.palette.graph_background, area);
+        canvas.border(self.palette.graph_border, 0.5, area);
+
+        if history.len() < 2 {
+            return;
+        }
+
+        // All series share the scale so births & deaths can be compared with the
+        // population
+        let max = history.iter().map(|m| m.population.max(m.births).max(m.deaths)).max().unwrap_or(0).max(1);
+        let dx = width / (history.len() - 1) as f64;
+
+        for (series, &color) in self.palette.graph_series.iter().enumerate() {
+            let point = |(i, m): (usize, &Metrics)| {
+                let value = [m.population, m.births, m.deaths][series];
+                [x + i as f64 * dx, y + height - value as f64 / max as f64 * height]
+            };
+
+            let points = history.iter().enumerate().map(point).collect::<Vec<_>>();
+            for pair in points.windows(2) {
+                canvas.line(color, 0.5, pair[0], pair[1]);
+            }
+        }
+    }
+
+    /// Returns the position on the board under the window position, in cells
+    pub fn pos_to_cell(&self, pos: [f64; 2]) -> [f64; 2] {
+        let scale = self.scale();
+        let [x, y] = self.camera.origin;
+        [pos[0] / scale + x, pos[1] / scale + y]
+    }
+
+    /// Returns the cell under the window position, negative left of or above
+    /// the board
+    pub fn pos_to_indices(&self, pos: [f64; 2]) -> [isize; 2] {
+        self.pos_to_cell(pos).map(|n| n.floor() as isize)
+    }
+
+    /// Returns the size of the window showing that many cells at the current zoom
+    pub fn window_size(&self, dims: [usize; 2]) -> [f64; 2] {
+        [dims[0] as f64 * self.scale(), dims[1] as f64 * self.scale()]
+    }
+
+    /// Scales the view by the factor, keeping the cell under the window position
+    /// in place
+    pub fn zoom_at(&mut self, factor: f64, pos: [f64; 2]) {
+        let [col, row] = self.pos_to_cell(pos);
+        self.camera.zoom = (self.camera.zoom * factor).clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM);
+
+        let scale = self.scale();
+        self.camera.origin = [col - pos[0] / scale, row - pos[1] / scale];
+    }
+
+    /// Moves the view by the distance in window coordinates
+    pub fn pan(&mut self, delta: [f64; 2]) {
+        let scale = self.scale();
+        self.camera.origin[0] -= delta[0] / scale;
+        self.camera.origin[1] -= delta[1] / scale;
+    }
+
+    /// Zooms & moves the view so the `[left, top, cols, rows]` region of the board
+    /// fills the window, centered
+    pub fn fit(&mut self, region: [usize; 4], window: [f64; 2]) {
+        let [left, top, cols, rows] = region.map(|n| n as f64);
+        let (cols, rows) = (cols.max(1.0), rows.max(1.0));
+
+        let scale = (window[0] / cols).min(window[1] / rows);
+        self.camera.zoom = (scale / self.cell_size).clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM);
+
+        let scale = self.scale();
+        self.camera.origin = [
+            left + cols / 2.0 - window[0] / scale / 2.0,
+            top + rows / 2.0 - window[1] / scale / 2.0,
+        ];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns;
+
+    /// Draws the framebuffer as text, `#` for white, `+` for other visible colors
+    fn ascii(fb: &Framebuffer) -> String {
+        let mut s = String::new();
+        for y in 0..fb.height() {
+            for x in 0..fb.width() {
+                s.push(match fb.pixel(x, y) {
+                    [255, 255, 255, 255] => '#',
+                    [0, 0, 0, _] | [_, _, _, 0] => '.',
+                    _ => '+',
+                });
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    #[test]
+    fn framebuffer() {
+        let mut fb = Framebuffer::new(4, 3);
+        assert_eq!(fb.pixel(0, 0), [0, 0, 0, 0]);
+
+        fb.clear([1.0, 0.0, 0.0, 1.0]);
+        fb.rectangle([0.0, 0.0, 1.0, 0.5], [1.0, 1.0, 2.0, 5.0]);
+        assert_eq!(fb.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(fb.pixel(1, 1), [128, 0, 128, 255]);
+        assert_eq!(fb.pixel(2, 2), [128, 0, 128, 255]);
+        assert_eq!(fb.pixel(3, 2), [255, 0, 0, 255]);
+
+        let mut fb = Framebuffer::new(5, 5);
+        fb.border([1.0; 4], 0.5, [1.5, 1.5, 2.0, 2.0]);
+        fb.line([1.0; 4], 0.5, [0.5, 4.5], [4.5, 0.5]);
+        assert_eq!(ascii(&fb), concat!(
+            "....#\n",
+            ".###.\n",
+            ".###.\n",
+            ".###.\n",
+            "#....\n",
+        ));
+    }
+
+    #[test]
+    fn renderer() {
+        let renderer = Renderer { cell_size: 2.0, show_age: false, ..Renderer::default() };
+        let mut fb = renderer.render(&Grid::from_slice(5, 4, &[CgolCell::Dead; 20]));
+        assert_eq!((fb.width(), fb.height()), (10, 8));
+
+        renderer.draw_grid(&patterns::GLIDER, &mut fb);
+        renderer.draw_brush(&patterns::BLOCK_1, [4, 3], &mut fb);
+        assert_eq!(ascii(&fb), concat!(
+            "..##......\n",
+            "..##......\n",
+            "....##....\n",
+            "....##....\n",
+            "######....\n",
+            "######....\n",
+            "........++\n",
+            "........++\n",
+        ));
+    }
+
+    #[test]
+    fn camera() {
+        let mut renderer = Renderer::default();
+        assert_eq!(renderer.pos_to_indices([9.0, 3.0]), [2, 0]);
+
+        // The cell under the pointer stays in place
+        renderer.zoom_at(2.0, [9.0, 3.0]);
+        assert_eq!(renderer.scale(), 8.0);
+        assert_eq!(renderer.pos_to_cell([9.0, 3.0]), [2.25, 0.75]);
+
+        renderer.pan([-16.0, 8.0]);
+        assert_eq!(renderer.pos_to_indices([0.0, 8.0]), [3, 0]);
+        assert_eq!(renderer.pos_to_indices([0.0, 0.0]), [3, -1]);
+        assert_eq!(renderer.pos_to_indices([-30.0, 0.0]), [-1, -1]);
+        assert_eq!(renderer.window_size([10, 5]), [80.0, 40.0]);
+
+        // Fitting a region centers it, keeping its aspect ratio
+        renderer.fit([10, 20, 4, 2], [80.0, 80.0]);
+        assert_eq!(renderer.scale(), 20.0);
+        assert_eq!(renderer.camera.origin, [10.0, 19.0]);
+
+        renderer.zoom_at(1000.0, [0.0, 0.0]);
+        assert_eq!(renderer.camera.zoom, Camera::MAX_ZOOM);
+
+        // The camera affects drawing but not rendering to a new image
+        let mut fb = Framebuffer::new(2, 2);
+        renderer.fit([0, 0, 2, 2], [2.0, 2.0]);
+        renderer.draw_grid(&patterns::BLINKER.transposed(), &mut fb);
+        assert_eq!(ascii(&fb), "##\n..\n");
+        assert_eq!(renderer.render(&patterns::BLOCK_1).width(), 4);
+    }
+
+    /// Canvas recording the rectangles drawn onto it
+    struct Rectangles([f64; 2], Vec<(Color, [f64; 4])>);
+
+    impl Canvas for Rectangles {
+        fn size(&self) -> [f64; 2] { self.0 }
+        fn clear(&mut self, _color: Color) {}
+        fn rectangle(&mut self, color: Color, rect: [f64; 4]) { self.1.push((color, rect)); }
+        fn border(&mut self, _color: Color, _radius: f64, _rect: [f64; 4]) {}
+        fn line(&mut self, _color: Color, _radius: f64, _from: [f64; 2], _to: [f64; 2]) {}
+    }
+
+    #[test]
+    fn mipmap() {
+        let grid = Grid::from_slice(5, 3, &[
+            CgolCell::Live(0), CgolCell::Live(0), CgolCell::Dead, CgolCell::Dead, CgolCell::Live(0),
+            CgolCell::Live(0), CgolCell::Dead, CgolCell::Dead, CgolCell::Dead, CgolCell::Dead,
+            CgolCell::Dead, CgolCell::Dead, CgolCell::Dead, CgolCell::Dead, CgolCell::Live(0),
+        ]);
+        let mipmap = Mipmap::new(&grid);
+
+        assert_eq!(mipmap.counts(2), Some(&Grid::from_slice(3, 2, &[3, 0, 1, 0, 0, 1])));
+        assert_eq!(mipmap.counts(4), Some(&Grid::from_slice(2, 1, &[3, 2])));
+        assert_eq!(mipmap.counts(8), Some(&Grid::from_slice(1, 1, &[5])));
+        assert_eq!(mipmap.counts(16), None);
+        assert_eq!(mipmap.counts(3), None);
+
+        // Blocks cut off by the edges only count the cells within the grid
+        assert_eq!(mipmap.density(2, 0, 0), 0.75);
+        assert_eq!(mipmap.density(2, 2, 1), 1.0);
+        assert_eq!(mipmap.density(4, 1, 0), 2.0 / 3.0);
+    }
+
+    #[test]
+    fn level_of_detail() {
+        let mut grid = Grid::from_slice(8, 2, &[CgolCell::Live(0); 16]);
+        grid[(3, 0)] = CgolCell::Dead;
+        grid[(7, 1)] = CgolCell::Dead;
+
+        // Runs of cells are drawn as one rectangle, & only cells on the canvas
+        let mut renderer = Renderer { cell_size: 2.0, show_age: false, ..Renderer::default() };
+        let mut canvas = Rectangles([10.0, 10.0], Vec::new());
+        renderer.draw_grid(&grid, &mut canvas);
+        let rects = canvas.1.iter().map(|(_, rect)| *rect).collect::<Vec<_>>();
+        assert_eq!(rects, [[0.0, 0.0, 6.0, 2.0], [8.0, 0.0, 2.0, 2.0], [0.0, 2.0, 10.0, 2.0]]);
+
+        // Cells smaller than a pixel are drawn as blocks by density
+        renderer.camera.zoom = 0.2;
+        let mut canvas = Rectangles([10.0, 10.0], Vec::new());
+        renderer.draw_grid(&grid, &mut canvas);
+        assert_eq!(canvas.1, [(Palette::DARK.fade(0.875), [0.0, 0.0, 3.2, 0.8])]);
+
+        let mut grid = Grid::new(8, 8);
+        for ((col, row), cell) in &mut grid {
+            if col < 4 || (row < 4 && col % 2 == 0) {
+                *cell = CgolCell::Live(0);
+            }
+        }
+        let mut fb = Framebuffer::new(2, 2);
+        Renderer { cell_size: 0.25, ..Renderer::default() }.draw_grid(&grid, &mut fb);
+        assert_eq!(ascii(&fb), "#+\n#.\n");
+        assert_eq!(fb.pixel(1, 0), [128, 128, 128, 255]);
+
+        // Cells too small to see are drawn as one block covering the whole grid
+        for &cell_size in &[1e-20, 0.0] {
+            let mut canvas = Rectangles([10.0, 10.0], Vec::new());
+            Renderer { cell_size, ..Renderer::default() }.draw_grid(&grid, &mut canvas);
+            assert_eq!(canvas.1.len(), 1);
+            assert_eq!(canvas.1[0].0, Palette::DARK.fade(40.0 / 64.0));
+        }
+    }
+
+    #[test]
+    fn graph() {
+        let renderer = Renderer { palette: Palette::LIGHT, ..Renderer::default() };
+        let mut canvas = Rectangles([100.0, 100.0], Vec::new());
+        renderer.draw_graph(&VecDeque::new(), [10.0, 10.0, 50.0, 20.0], &mut canvas);
+        assert_eq!(canvas.1, [(Palette::LIGHT.graph_background, [10.0, 10.0, 50.0, 20.0])]);
+    }
+
+    #[test]
+    fn png() {
+        let renderer = Renderer { cell_size: 3.0, gridlines: true, palette: Palette::LIGHT, ..Renderer::default() };
+        let fb = renderer.render(&Grid::from_slice(3, 1, &[CgolCell::Live(0), CgolCell::Live(3), CgolCell::Dead]));
+
+        assert_eq!((fb.width(), fb.height()), (9, 3));
+        assert_eq!(fb.pixel(0, 0), [0, 0, 0, 255]);
+        assert_eq!(fb.pixel(2, 1), [204, 204, 204, 255]);
+        assert_eq!(fb.pixel(3, 2), [191, 191, 191, 255]);
+        assert_eq!(fb.pixel(6, 0), [255, 255, 255, 255]);
+
+        let mut data = Vec::new();
+        fb.write_png(&mut data).unwrap();
+
+        let (info, mut reader) = png::Decoder::new(&data[..]).read_info().unwrap();
+        let mut pixels = vec![0; info.buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (9, 3));
+        assert_eq!(pixels, fb.as_bytes());
+    }
+
+    #[test]
+    fn gradients() {
+        let palette = |ages: &str| Palette { ages: ages.parse().unwrap(), ..Palette::DARK };
+
+        assert_eq!(palette("fade").aged(1), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(palette("heat").aged(0), Gradient::HEAT[0]);
+        assert_eq!(palette("viridis").aged(200), Gradient::VIRIDIS[4]);
+
+        let newborn = palette("newborn:#ff0000");
+        assert_eq!(newborn.aged(0), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(newborn.aged(1), Palette::DARK.live);
+
+        // Cells move along the gradient as fast as they fade, past the middle
+        // color at age 1
+        let custom = palette("#000000,#ff0000,#ffffff");
+        assert_eq!(custom.ages.name(), "custom");
+        assert_eq!(custom.aged(0), [0.0, 0.0, 0.0, 1.0]);
+        let [r, g, b, _] = custom.aged(1);
+        assert!(r == 1.0 && (0.1..0.12).contains(&g) && g == b, "{:?}", custom.aged(1));
+        assert_eq!(custom.aged(9), [1.0; 4]);
+
+        assert!("#ff0000".parse::<Gradient>().is_err());
+        assert!("rainbow".parse::<Gradient>().is_err());
+    }
+
+    #[test]
+    fn palette() {
+        assert_eq!("dark".parse(), Ok(Palette::DARK));
+        assert_eq!("#ffffff,#000000,#cccccc".parse(), Ok(Palette::LIGHT));
+        assert!("#ffffff,#000000".parse::<Palette>().is_err());
+        assert_eq!(parse_color("#ff8000"), Ok([1.0, 128.0 / 255.0, 0.0, 1.0]));
+        assert!(parse_color("ff8000").is_err());
+    }
+}
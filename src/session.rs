@@ -0,0 +1,119 @@
+use seagull::{snapshot, Automaton, Cgol};
+use std::{fmt::Write, fs};
+
+/// Default location of the session file if none is given on the command line
+pub const DEFAULT_PATH: &str = "session.sgul";
+
+/// GUI settings saved along with the automaton
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub step_millis: Option<u64>,
+    pub category: Option<String>,
+    pub brush: Option<String>,
+}
+
+/// Writes the automaton as a snapshot followed by the settings as `name value`
+/// lines
+pub fn save(path: &str, cgol: &Automaton<Cgol>, settings: &Settings) -> Result<(), String> {
+    let mut data = Vec::new();
+    snapshot::save(cgol, &mut data).map_err(|e| e.to_string())?;
+
+    let mut lines = String::new();
+    if let Some(step_millis) = settings.step_millis {
+        writeln!(lines, "step_millis {}", step_millis).unwrap();
+    }
+    if let Some(category) = &settings.category {
+        writeln!(lines, "category {}", category).unwrap();
+    }
+    if let Some(brush) = &settings.brush {
+        writeln!(lines, "brush {}", brush).unwrap();
+    }
+    data.extend(lines.as_bytes());
+
+    fs::write(path, data).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Reads a session file, or a plain snapshot in which case no settings are set
+pub fn load(path: &str) -> Result<(Automaton<Cgol>, Settings), String> {
+    let data = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut rest = &data[..];
+    let cgol = snapshot::load(&mut rest).map_err(|e| format!("{}: {}", path, e))?;
+
+    let lines = std::str::from_utf8(rest).map_err(|_| format!("{}: invalid settings", path))?;
+    let mut settings = Settings::default();
+
+    for line in lines.lines() {
+        match line.split_once(' ') {
+            Some(("step_millis", value)) => settings.step_millis = value.parse().ok(),
+            Some(("category", value)) => settings.category = Some(value.to_string()),
+            Some(("brush", value)) => settings.brush = Some(value.to_string()),
+            // Settings from other versions are ignored
+            _ => (),
+        }
+    }
+
+    Ok((cgol, settings))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use seagull::cgol::patterns;
+
+    /// Returns a path in the temporary directory unique to the test
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("cgol-{}-{}.sgul", std::process::id(), name));
+        path.to_str().unwrap().to_string()
+    }
+
+    fn glider() -> Automaton<Cgol> {
+        let mut cgol = Automaton::<Cgol>::new([8, 6]);
+        cgol.put(&patterns::GLIDER, 1, 1);
+        cgol.step();
+        cgol
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round-trip");
+        let settings = Settings {
+            step_millis: Some(128),
+            category: Some("spaceship".to_string()),
+            brush: Some("lightweight spaceship".to_string()),
+        };
+
+        save(&path, &glider(), &settings).unwrap();
+        let (cgol, loaded) = load(&path).unwrap();
+        assert_eq!(cgol.snapshot(), glider().snapshot());
+        assert_eq!(loaded, settings);
+
+        // Settings from other versions are skipped
+        let mut data = fs::read(&path).unwrap();
+        data.extend(b"theme dark\nstep_millis 32\n");
+        fs::write(&path, data).unwrap();
+        let (_, loaded) = load(&path).unwrap();
+        assert_eq!(loaded, Settings { step_millis: Some(32), ..settings });
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn plain_snapshot() {
+        let path = temp_path("plain-snapshot");
+        let mut data = Vec::new();
+        snapshot::save(&glider(), &mut data).unwrap();
+        fs::write(&path, &data).unwrap();
+
+        let (cgol, settings) = load(&path).unwrap();
+        assert_eq!(cgol.snapshot(), glider().snapshot());
+        assert_eq!(settings, Settings::default());
+
+        // Whatever follows the snapshot has to be text
+        data.extend([b's', 0xff, b'\n']);
+        fs::write(&path, &data).unwrap();
+        assert_eq!(load(&path).err(), Some(format!("{}: invalid settings", path)));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
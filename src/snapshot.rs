@@ -0,0 +1,255 @@
+//! Compact binary format for saving automatons
+//!
+//! A snapshot starts with a header:
+//!
+//! | bytes | content                                            |
+//! |-------|----------------------------------------------------|
+//! | 4     | magic `SGUL`                                       |
+//! | 1     | format version, currently 1                        |
+//! | 1     | flags, bit 0 is set if ages follow the cells       |
+//! | 1 + n | length & name of the rule, e.g. `B3/S23`           |
+//! | 4 + 4 | columns & rows, little endian                      |
+//! | 8     | generation, little endian                          |
+//!
+//! The cells follow in row-major order as lengths of alternating runs of dead
+//! and live cells, starting with dead cells. If ages are stored, the age of each
+//! live cell follows in the same order. All run lengths and ages are LEB128
+//! varints, so sparse boards take up a few bytes regardless of their size.
+
+use crate::{
+    automaton::{Automaton, Ruleset, Snapshot},
+    grid::Grid,
+};
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+
+const MAGIC: &[u8; 4] = b"SGUL";
+const VERSION: u8 = 1;
+const HAS_AGES: u8 = 1;
+
+/// Most cells a snapshot may have, so that a corrupt header can't make loading
+/// allocate an unbounded amount of memory
+pub const MAX_CELLS: usize = 1 << 26;
+
+/// Ruleset whose cells can be stored in a snapshot
+pub trait Encode: Ruleset {
+    /// Name of the rule stored in snapshots, which have to match to be loaded
+    const RULE: &'static str;
+
+    /// Returns the state of a cell stored with the given liveness & age
+    fn decode(live: bool, age: Option<u64>) -> Self::State;
+}
+
+/// Writes the current state of the automaton as a snapshot
+pub fn save<R: Encode>(automaton: &Automaton<R>, writer: &mut impl Write) -> io::Result<()> {
+    let cells = automaton.cells();
+    let has_ages = cells.into_iter().any(|(_, cell)| R::age(cell).is_some());
+
+    let dimension = |n: usize| {
+        u32::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "grid too large"))
+    };
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, if has_ages { HAS_AGES } else { 0 }])?;
+    let rule_len = u8::try_from(R::RULE.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "rule name too long"))?;
+    writer.write_all(&[rule_len])?;
+    writer.write_all(R::RULE.as_bytes())?;
+    writer.write_all(&dimension(cells.cols())?.to_le_bytes())?;
+    writer.write_all(&dimension(cells.rows())?.to_le_bytes())?;
+    writer.write_all(&automaton.generation().to_le_bytes())?;
+
+    let mut live = false;
+    let mut run = 0u64;
+    for (_, cell) in cells {
+        if R::is_live(cell) != live {
+            write_varint(writer, run)?;
+            live = !live;
+            run = 0;
+        }
+        run += 1;
+    }
+    // An empty grid has no runs at all
+    if run > 0 {
+        write_varint(writer, run)?;
+    }
+
+    if has_ages {
+        for (_, cell) in cells.into_iter().filter(|(_, cell)| R::is_live(cell)) {
+            write_varint(writer, R::age(cell).unwrap_or(0))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads an automaton from a snapshot, failing if the data isn't a snapshot, was
+/// saved by a newer version of the format or for a different rule, or has more
+/// than `MAX_CELLS` cells
+pub fn load<R: Encode>(reader: &mut impl Read) -> Result<Automaton<R>, String> {
+    let mut magic = [0; 4];
+    read(reader, &mut magic)?;
+    if &magic != MAGIC {
+        return Err("not a snapshot".to_string());
+    }
+
+    let [version, flags] = read_array(reader)?;
+    if version != VERSION {
+        return Err(format!("unsupported snapshot version: {}", version));
+    }
+
+    let [rule_len] = read_array(reader)?;
+    let mut rule = vec![0; rule_len as usize];
+    read(reader, &mut rule)?;
+    if rule != R::RULE.as_bytes() {
+        return Err(format!(
+            "snapshot is for rule {}, expected {}",
+            String::from_utf8_lossy(&rule),
+            R::RULE,
+        ));
+    }
+
+    let cols = u32::from_le_bytes(read_array(reader)?) as usize;
+    let rows = u32::from_le_bytes(read_array(reader)?) as usize;
+    let generation = u64::from_le_bytes(read_array(reader)?);
+
+    let area = cols
+        .checked_mul(rows)
+        .filter(|&area| area <= MAX_CELLS)
+        .ok_or_else(|| format!("snapshot grid is too large: {}x{}", cols, rows))?;
+
+    // Runs are read before allocating the grid, so truncated data fails early
+    let mut runs = Vec::new();
+    let mut remaining = area;
+    while remaining > 0 {
+        let run = read_varint(reader)?;
+        if run > remaining as u64 {
+            return Err("cell runs exceed the grid".to_string());
+        }
+
+        runs.push(run as usize);
+        remaining -= run as usize;
+    }
+
+    let mut grid = Grid::new(cols, rows);
+    let mut cells = (&mut grid).into_iter().map(|(_, cell)| cell);
+    for (i, run) in runs.into_iter().enumerate() {
+        let live = i % 2 == 1;
+        for cell in cells.by_ref().take(run) {
+            let age = if live && flags & HAS_AGES != 0 { Some(read_varint(reader)?) } else { None };
+            *cell = R::decode(live, age);
+        }
+    }
+
+    Ok(Automaton::from_snapshot(&Snapshot { generation, cells: grid }))
+}
+
+fn read(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), String> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => "unexpected end of snapshot".to_string(),
+        _ => e.to_string(),
+    })
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], String> {
+    let mut buf = [0; N];
+    read(reader, &mut buf)?;
+    Ok(buf)
+}
+
+fn write_varint(writer: &mut impl Write, mut n: u64) -> io::Result<()> {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+
+        if n == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> Result<u64, String> {
+    let mut n = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let [byte] = read_array(reader)?;
+        n |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+
+    Err("invalid varint in snapshot".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{cgol::patterns, Cgol};
+
+    fn saved(cgol: &Automaton<Cgol>) -> Vec<u8> {
+        let mut data = Vec::new();
+        save(cgol, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut cgol = Automaton::<Cgol>::new([40, 30]);
+        cgol.put(&patterns::R_PENTOMINO, 18, 12);
+        for _ in 0..50 {
+            cgol.step();
+        }
+
+        let mut loaded = load::<Cgol>(&mut &saved(&cgol)[..]).unwrap();
+        assert_eq!(loaded.snapshot(), cgol.snapshot());
+
+        loaded.step();
+        cgol.step();
+        assert_eq!(loaded.snapshot(), cgol.snapshot());
+
+        // Loading reads exactly what was saved, so more data can follow
+        let empty = Automaton::<Cgol>::new([3, 0]);
+        let mut data = &saved(&empty)[..];
+        assert_eq!(load::<Cgol>(&mut data).unwrap().snapshot(), empty.snapshot());
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let mut cgol = Automaton::<Cgol>::new([5000, 5000]);
+        cgol.put(&patterns::GLIDER, 2500, 2500);
+
+        let data = saved(&cgol);
+        assert_eq!(&data[..6], b"SGUL\x01\x01");
+        assert!(data.len() < 64, "{} bytes", data.len());
+    }
+
+    #[test]
+    fn errors() {
+        let mut cgol = Automaton::<Cgol>::new([8, 8]);
+        cgol.put(&patterns::GLIDER, 2, 2);
+        let data = saved(&cgol);
+
+        let load = |data: &[u8]| load::<Cgol>(&mut &data[..]).err();
+
+        assert_eq!(load(b"GIF89a"), Some("not a snapshot".to_string()));
+        assert_eq!(load(&data[..data.len() - 1]), Some("unexpected end of snapshot".to_string()));
+
+        let mut newer = data.clone();
+        newer[4] = 2;
+        assert_eq!(load(&newer), Some("unsupported snapshot version: 2".to_string()));
+
+        let mut huge = data.clone();
+        huge[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(load(&huge), Some(format!("snapshot grid is too large: {}x8", u32::MAX)));
+
+        let mut other_rule = data;
+        other_rule[7..13].copy_from_slice(b"B36/S2");
+        assert_eq!(load(&other_rule), Some("snapshot is for rule B36/S2, expected B3/S23".to_string()));
+    }
+}
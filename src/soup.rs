@@ -0,0 +1,168 @@
+use crate::{cgol::CgolCell, grid::Grid};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::str::FromStr;
+
+/// Symmetry imposed on a generated soup (named after the apgsearch symmetries)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// No symmetry
+    C1,
+    /// Invariant under 180° rotation
+    C2,
+    /// Invariant under 90° rotation, requires a square region
+    C4,
+    /// Invariant under all rotations and reflections, requires a square region
+    D8,
+}
+
+impl Symmetry {
+    /// Returns all the cells that the given cell is mapped to under the symmetry
+    fn orbit(self, (col, row): (usize, usize), cols: usize, rows: usize) -> Vec<(usize, usize)> {
+        let (c, r) = (cols - 1 - col, rows - 1 - row);
+
+        match self {
+            Self::C1 => vec![(col, row)],
+            Self::C2 => vec![(col, row), (c, r)],
+            Self::C4 => vec![(col, row), (r, col), (c, r), (row, c)],
+            Self::D8 => vec![
+                (col, row), (r, col), (c, r), (row, c),
+                (c, row), (row, col), (col, r), (r, c),
+            ],
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "C1" => Ok(Self::C1),
+            "C2" => Ok(Self::C2),
+            "C4" => Ok(Self::C4),
+            "D8" => Ok(Self::D8),
+            _ => Err(format!("unknown symmetry: {}", s)),
+        }
+    }
+}
+
+/// Reproducible random soup generator
+///
+/// The same seed and parameters always produce the same soup, so soups can be
+/// shared just by their seed.
+#[derive(Debug, Clone)]
+pub struct Soup {
+    seed: u64,
+    density: f64,
+    cols: usize,
+    rows: usize,
+    symmetry: Symmetry,
+}
+
+impl Soup {
+    /// Creates a 16x16 asymmetric soup generator with density 0.5
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            density: 0.5,
+            cols: 16,
+            rows: 16,
+            symmetry: Symmetry::C1,
+        }
+    }
+
+    /// Sets the probability of each cell being alive
+    pub fn density(mut self, density: f64) -> Self {
+        assert!((0.0..=1.0).contains(&density), "density must be within 0..=1");
+        self.density = density;
+        self
+    }
+
+    /// Sets the dimensions of the region filled with the soup
+    pub fn size(mut self, cols: usize, rows: usize) -> Self {
+        self.cols = cols;
+        self.rows = rows;
+        self
+    }
+
+    /// Sets the symmetry imposed on the soup, which must be C1 or C2 for a
+    /// region that isn't square
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    pub const fn seed(&self) -> u64 { self.seed }
+
+    /// Returns a generator with the same parameters but a different seed
+    pub fn with_seed(&self, seed: u64) -> Self {
+        Self { seed, ..self.clone() }
+    }
+
+    /// Generates the soup as a pattern that can be placed with `Automaton::put`
+    pub fn generate(&self) -> Grid<CgolCell> {
+        if let Symmetry::C4 | Symmetry::D8 = self.symmetry {
+            assert_eq!(self.cols, self.rows, "{:?} symmetry requires a square region", self.symmetry);
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut noise = Grid::<bool>::new(self.cols, self.rows);
+        for (_, alive) in &mut noise {
+            *alive = rng.gen_bool(self.density);
+        }
+
+        // Each cell takes the value of the first cell of its orbit, so that the
+        // whole orbit ends up in the same state
+        let mut soup = Grid::new(self.cols, self.rows);
+        for (pos, cell) in &mut soup {
+            let first = self.symmetry
+                .orbit(pos, self.cols, self.rows)
+                .into_iter()
+                .min_by_key(|&(col, row)| (row, col))
+                .unwrap();
+
+            if noise[first] {
+                *cell = CgolCell::Live(0);
+            }
+        }
+
+        soup
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let a = Soup::new(42).size(20, 10).generate();
+        let b = Soup::new(42).size(20, 10).generate();
+        let c = Soup::new(43).size(20, 10).generate();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn density() {
+        let empty = Soup::new(1).density(0.0).generate();
+        let full = Soup::new(1).density(1.0).generate();
+
+        assert!(empty.into_iter().all(|(_, c)| *c == CgolCell::Dead));
+        assert!(full.into_iter().all(|(_, c)| *c != CgolCell::Dead));
+    }
+
+    #[test]
+    fn symmetry() {
+        for &symmetry in &[Symmetry::C2, Symmetry::C4, Symmetry::D8] {
+            let soup = Soup::new(7).size(9, 9).symmetry(symmetry).generate();
+
+            for (pos, cell) in &soup {
+                for other in symmetry.orbit(pos, 9, 9) {
+                    assert_eq!(soup[other], *cell, "{:?} at {:?}", symmetry, pos);
+                }
+            }
+        }
+    }
+}
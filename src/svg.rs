@@ -0,0 +1,350 @@
+use crate::{
+    cgol::CgolCell,
+    grid::Grid,
+    render::{Color, Palette},
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    mem,
+    str::FromStr,
+};
+
+/// Shape in which cells are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Square,
+    Circle,
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "square" => Ok(Self::Square),
+            "circle" => Ok(Self::Circle),
+            _ => Err(format!("unknown cell shape: {}", s)),
+        }
+    }
+}
+
+/// Writes grids as SVG images
+///
+/// Cells of the same color are drawn as a single path, with adjacent square cells
+/// merged into rectangles, which keeps the files small.
+#[derive(Debug, Clone)]
+pub struct SvgWriter {
+    cell_size: f64,
+    shape: Shape,
+    gridlines: bool,
+    labels: Option<usize>,
+    palette: Palette,
+    highlights: Vec<([usize; 4], Color)>,
+}
+
+impl Default for SvgWriter {
+    fn default() -> Self {
+        Self {
+            cell_size: 10.0,
+            shape: Shape::Square,
+            gridlines: false,
+            labels: None,
+            palette: Palette::LIGHT,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl SvgWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cell_size(mut self, cell_size: f64) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn gridlines(mut self, gridlines: bool) -> Self {
+        self.gridlines = gridlines;
+        self
+    }
+
+    /// Labels every `step`th column & row with its index, in a margin above and
+    /// to the left of the grid
+    pub fn labels(mut self, step: usize) -> Self {
+        assert!(step > 0);
+        self.labels = Some(step);
+        self
+    }
+
+    /// Sets the background & gridline colors as well as the color of live cells
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Outlines the `[left, top, cols, rows]` region of the grid in the color
+    pub fn highlight(mut self, region: [usize; 4], color: Color) -> Self {
+        self.highlights.push((region, color));
+        self
+    }
+
+    /// Writes the live cells of the grid
+    pub fn write(&self, grid: &Grid<CgolCell>, writer: impl Write) -> io::Result<()> {
+        let live = self.palette.live;
+        self.write_states(grid, |cell| matches!(cell, CgolCell::Live(_)).then_some(live), writer)
+    }
+
+    /// Writes a grid of any cell type, drawing each cell in the color returned
+    /// for its state or leaving it empty for `None`
+    pub fn write_states<S>(
+        &self,
+        grid: &Grid<S>,
+        color: impl Fn(&S) -> Option<Color>,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        let size = self.cell_size;
+        let margin = if self.labels.is_some() { 2.0 * size } else { 0.0 };
+        // Subtracted from zero so no margin isn't written as `-0`
+        let origin = 0.0 - margin;
+        let (width, height) = (grid.cols() as f64 * size, grid.rows() as f64 * size);
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{x} {y} {w} {h}">"#,
+            x = origin,
+            y = origin,
+            w = width + margin,
+            h = height + margin,
+        )
+        .unwrap();
+        writeln!(svg, r#"<rect width="{}" height="{}"{}/>"#, width, height, fill(self.palette.background)).unwrap();
+
+        // Cells are grouped by color in the order the colors first appear
+        let mut colors: Vec<(Color, Vec<(usize, usize)>)> = Vec::new();
+        for ((col, row), cell) in grid {
+            if let Some(c) = color(cell) {
+                match colors.iter_mut().find(|(other, _)| *other == c) {
+                    Some((_, cells)) => cells.push((col, row)),
+                    None => colors.push((c, vec![(col, row)])),
+                }
+            }
+        }
+
+        for (color, cells) in &colors {
+            let data = match self.shape {
+                Shape::Square => square_path(cells, size),
+                Shape::Circle => circle_path(cells, size),
+            };
+            writeln!(svg, r#"<path d="{}"{}/>"#, data.trim_end(), fill(*color)).unwrap();
+        }
+
+        if self.gridlines {
+            let mut data = String::new();
+            for col in 0..=grid.cols() {
+                write!(data, "M{} 0V{} ", col as f64 * size, height).unwrap();
+            }
+            for row in 0..=grid.rows() {
+                write!(data, "M0 {}H{} ", row as f64 * size, width).unwrap();
+            }
+            writeln!(
+                svg,
+                r#"<path d="{}" fill="none"{} stroke-width="{}"/>"#,
+                data.trim_end(),
+                stroke(self.palette.gridlines),
+                size / 20.0,
+            )
+            .unwrap();
+        }
+
+        if let Some(step) = self.labels {
+            let font = size * 0.8;
+            writeln!(
+                svg,
+                r#"<g font-family="monospace" font-size="{}" text-anchor="middle" dominant-baseline="central"{}>"#,
+                font,
+                fill(self.palette.live),
+            )
+            .unwrap();
+            for col in (0..grid.cols()).step_by(step) {
+                let x = (col as f64 + 0.5) * size;
+                writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, x, -size, col).unwrap();
+            }
+            for row in (0..grid.rows()).step_by(step) {
+                let y = (row as f64 + 0.5) * size;
+                writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, -size, y, row).unwrap();
+            }
+            writeln!(svg, "</g>").unwrap();
+        }
+
+        for &([left, top, cols, rows], color) in &self.highlights {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none"{} stroke-width="{}"/>"#,
+                left as f64 * size,
+                top as f64 * size,
+                cols as f64 * size,
+                rows as f64 * size,
+                stroke(color),
+                size / 5.0,
+            )
+            .unwrap();
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+        writer.write_all(svg.as_bytes())
+    }
+}
+
+/// Returns path data covering the cells, with runs of cells in a row merged and
+/// equal runs in consecutive rows merged into rectangles
+fn square_path(cells: &[(usize, usize)], size: f64) -> String {
+    // Runs as `(start, end, row)` with an exclusive end, in row-major order
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for &(col, row) in cells {
+        match runs.last_mut() {
+            Some((_, end, r)) if *r == row && *end == col => *end += 1,
+            _ => runs.push((col, col + 1, row)),
+        }
+    }
+
+    // Rectangles as `(start, end, top, bottom)`, extended while the run below
+    // has the same extent
+    let mut rects: Vec<(usize, usize, usize, usize)> = Vec::new();
+    // Indices of rectangles reaching the previous & current row
+    let (mut above, mut current) = (Vec::new(), Vec::new());
+    let mut last_row = None;
+
+    for (start, end, row) in runs {
+        if last_row != Some(row) {
+            above = if last_row.map(|r| r + 1) == Some(row) { mem::take(&mut current) } else { Vec::new() };
+            current.clear();
+            last_row = Some(row);
+        }
+
+        let extended = above.iter().position(|&i: &usize| rects[i].0 == start && rects[i].1 == end);
+        let i = match extended {
+            Some(j) => above.swap_remove(j),
+            None => {
+                rects.push((start, end, row, row));
+                rects.len() - 1
+            }
+        };
+        rects[i].3 = row + 1;
+        current.push(i);
+    }
+
+    let mut data = String::new();
+    for (start, end, top, bottom) in rects {
+        write!(
+            data,
+            "M{} {}h{}v{}h-{}z ",
+            start as f64 * size,
+            top as f64 * size,
+            (end - start) as f64 * size,
+            (bottom - top) as f64 * size,
+            (end - start) as f64 * size,
+        )
+        .unwrap();
+    }
+    data
+}
+
+/// Returns path data of a circle inscribed in each cell, drawn as two arcs
+fn circle_path(cells: &[(usize, usize)], size: f64) -> String {
+    let r = size / 2.0;
+    let mut data = String::new();
+    for &(col, row) in cells {
+        write!(
+            data,
+            "M{} {}a{r} {r} 0 1 0 {d} 0a{r} {r} 0 1 0 -{d} 0z ",
+            col as f64 * size,
+            (row as f64 + 0.5) * size,
+            r = r,
+            d = size,
+        )
+        .unwrap();
+    }
+    data
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn fill(color: Color) -> String {
+    match color[3] {
+        a if a >= 1.0 => format!(r#" fill="{}""#, hex(color)),
+        a => format!(r#" fill="{}" fill-opacity="{}""#, hex(color), a),
+    }
+}
+
+fn stroke(color: Color) -> String {
+    match color[3] {
+        a if a >= 1.0 => format!(r#" stroke="{}""#, hex(color)),
+        a => format!(r#" stroke="{}" stroke-opacity="{}""#, hex(color), a),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns;
+
+    fn svg(writer: &SvgWriter, grid: &Grid<CgolCell>) -> String {
+        let mut data = Vec::new();
+        writer.write(grid, &mut data).unwrap();
+        String::from_utf8(data).unwrap()
+    }
+
+    #[test]
+    fn merged_squares() {
+        assert_eq!(svg(&SvgWriter::new(), &patterns::GLIDER), concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="30" viewBox="0 0 30 30">"#, "\n",
+            r##"<rect width="30" height="30" fill="#ffffff"/>"##, "\n",
+            r##"<path d="M10 0h10v10h-10z M20 10h10v10h-10z M0 20h30v10h-30z" fill="#000000"/>"##, "\n",
+            "</svg>\n",
+        ));
+
+        let block = svg(&SvgWriter::new().cell_size(1.0), &patterns::BLOCK_2);
+        assert!(block.contains(r##"<path d="M0 0h2v2h-2z" fill="#000000"/>"##), "{}", block);
+    }
+
+    #[test]
+    fn options() {
+        let writer = SvgWriter::new()
+            .shape(Shape::Circle)
+            .gridlines(true)
+            .labels(2)
+            .highlight([0, 0, 1, 2], [1.0, 0.0, 0.0, 0.5]);
+        let data = svg(&writer, &patterns::BLINKER);
+
+        assert!(data.contains(r#"viewBox="-20 -20 30 50""#), "{}", data);
+        assert!(data.contains(r#"<path d="M0 5a5 5 0 1 0 10 0a5 5 0 1 0 -10 0z M0 15a5"#), "{}", data);
+        assert!(data.contains(r#"<path d="M0 0V30 M10 0V30 M0 0H10 M0 10H10 M0 20H10 M0 30H10""#), "{}", data);
+        assert!(data.contains(r#"<text x="-10" y="25">2</text>"#), "{}", data);
+        assert!(data.contains(r##"stroke="#ff0000" stroke-opacity="0.5""##), "{}", data);
+    }
+
+    #[test]
+    fn states() {
+        let grid = Grid::from_slice(3, 1, &[1u8, 2, 1]);
+        let color = |&s: &u8| Some([s as f32 / 2.0, 0.0, 0.0, 1.0]);
+
+        let mut data = Vec::new();
+        SvgWriter::new().write_states(&grid, color, &mut data).unwrap();
+        let data = String::from_utf8(data).unwrap();
+
+        assert!(data.contains(r##"<path d="M0 0h10v10h-10z M20 0h10v10h-10z" fill="#800000"/>"##), "{}", data);
+        assert!(data.contains(r##"<path d="M10 0h10v10h-10z" fill="#ff0000"/>"##), "{}", data);
+    }
+}
@@ -0,0 +1,263 @@
+//! Drawing of grids as text for terminals, with several cells packed into each
+//! character
+
+use crate::{
+    cgol::CgolCell,
+    grid::Grid,
+    render::{Color, Palette},
+};
+use std::{fmt::Write, str::FromStr};
+
+/// Characters cells are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyphs {
+    /// Half blocks, drawing 1 by 2 cells per character in two colors
+    HalfBlock,
+    /// Braille patterns, drawing 2 by 4 cells per character in one color
+    Braille,
+}
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half-block" => Ok(Self::HalfBlock),
+            "braille" => Ok(Self::Braille),
+            _ => Err(format!("unknown glyphs: {}", s)),
+        }
+    }
+}
+
+/// Draws grids as lines of text, colored with ANSI escape codes
+///
+/// Without colors, marked cells are drawn inverted.
+#[derive(Debug, Clone)]
+pub struct TextRenderer {
+    pub glyphs: Glyphs,
+    pub show_age: bool,
+    pub color: bool,
+    pub palette: Palette,
+}
+
+impl Default for TextRenderer {
+    fn default() -> Self {
+        Self {
+            glyphs: Glyphs::HalfBlock,
+            show_age: true,
+            color: true,
+            palette: Palette::DARK,
+        }
+    }
+}
+
+impl TextRenderer {
+    /// Returns the number of columns & rows of cells drawn as one character
+    pub fn cells_per_char(&self) -> [usize; 2] {
+        match self.glyphs {
+            Glyphs::HalfBlock => [1, 2],
+            Glyphs::Braille => [2, 4],
+        }
+    }
+
+    /// Returns the number of characters & lines needed to draw the cells
+    pub fn text_size(&self, [cols, rows]: [usize; 2]) -> [usize; 2] {
+        let [width, height] = self.cells_per_char();
+        [cols.div_ceil(width), rows.div_ceil(height)]
+    }
+
+    /// Returns the number of cells drawn within the characters & lines
+    pub fn grid_size(&self, [width, height]: [usize; 2]) -> [usize; 2] {
+        let [cols, rows] = self.cells_per_char();
+        [width * cols, height * rows]
+    }
+
+    /// Draws the `[left, top, cols, rows]` region of the grid as lines of text,
+    /// highlighting the cells for which `marked` returns true, such as the ones
+    /// under a brush
+    pub fn draw(&self, grid: &Grid<CgolCell>, region: [usize; 4], marked: impl Fn(usize, usize) -> bool) -> Vec<String> {
+        let [left, top, cols, rows] = region;
+        let cells = grid.crop(left, top, cols, rows);
+        let [width, height] = self.cells_per_char();
+        let [chars, lines] = self.text_size([cols, rows]);
+
+        // Cells past the edge of the region are dead and unmarked
+        let cell = |col: usize, row: usize| {
+            if col < cols && row < rows {
+                (cells[(col, row)], marked(left + col, top + row))
+            } else {
+                (CgolCell::Dead, false)
+            }
+        };
+
+        let mut text = Vec::with_capacity(lines);
+        for line in 0..lines {
+            let mut ansi = Ansi::default();
+            for ch in 0..chars {
+                let (col, row) = (ch * width, line * height);
+                match self.glyphs {
+                    Glyphs::HalfBlock => self.half_block(&mut ansi, cell(col, row), cell(col, row + 1)),
+                    Glyphs::Braille => {
+                        let mut block = [(CgolCell::Dead, false); 8];
+                        for (i, c) in block.iter_mut().enumerate() {
+                            *c = cell(col + i % 2, row + i / 2);
+                        }
+                        self.braille(&mut ansi, block);
+                    }
+                }
+            }
+            text.push(ansi.finish());
+        }
+
+        text
+    }
+
+    fn half_block(&self, ansi: &mut Ansi, top: (CgolCell, bool), bottom: (CgolCell, bool)) {
+        if !self.color {
+            let ch = match (is_drawn(top), is_drawn(bottom)) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            };
+            ansi.text.push(ch);
+            return;
+        }
+
+        let (top, bottom) = (self.cell_color(top), self.cell_color(bottom));
+        if top == bottom {
+            ansi.push(None, Some(bottom), ' ');
+        } else {
+            ansi.push(Some(top), Some(bottom), '▀');
+        }
+    }
+
+    /// Draws 8 cells in row-major order, 2 per row
+    fn braille(&self, ansi: &mut Ansi, block: [(CgolCell, bool); 8]) {
+        // Bits of the dots in the order of the cells
+        const DOTS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+
+        let drawn = |(cell, marked): (CgolCell, bool)| match self.color {
+            true => matches!(cell, CgolCell::Live(_)),
+            false => is_drawn((cell, marked)),
+        };
+        let bits = block.iter().zip(DOTS).filter(|(&c, _)| drawn(c)).fold(0, |bits, (_, dot)| bits | dot);
+        let ch = char::from_u32(0x2800 + bits).unwrap_or(' ');
+
+        if !self.color {
+            ansi.text.push(ch);
+            return;
+        }
+
+        // Dots share a single color, which is that of the youngest cell
+        let youngest = block
+            .iter()
+            .filter_map(|&(cell, _)| match cell {
+                CgolCell::Live(age) => Some(age),
+                CgolCell::Dead => None,
+            })
+            .min();
+        let marked = block.iter().any(|&(_, marked)| marked);
+
+        let background = self.cell_color((CgolCell::Dead, marked));
+        let foreground = youngest.map(|age| self.cell_color((CgolCell::Live(age), false)));
+        ansi.push(foreground, Some(background), ch);
+    }
+
+    fn cell_color(&self, (cell, marked): (CgolCell, bool)) -> [u8; 3] {
+        let color = match cell {
+            CgolCell::Live(age) if self.show_age => self.palette.aged(age),
+            CgolCell::Live(_) => self.palette.live,
+            CgolCell::Dead => self.palette.background,
+        };
+        let [r, g, b, _] = if marked { blend(color, self.palette.brush) } else { color };
+        [r, g, b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+/// Returns whether the cell is drawn without colors, with marked cells inverted
+fn is_drawn((cell, marked): (CgolCell, bool)) -> bool {
+    matches!(cell, CgolCell::Live(_)) != marked
+}
+
+fn blend(under: Color, over: Color) -> Color {
+    let a = over[3];
+    [
+        under[0] + (over[0] - under[0]) * a,
+        under[1] + (over[1] - under[1]) * a,
+        under[2] + (over[2] - under[2]) * a,
+        under[3],
+    ]
+}
+
+/// Line of text which only switches colors when they change
+#[derive(Default)]
+struct Ansi {
+    text: String,
+    foreground: Option<[u8; 3]>,
+    background: Option<[u8; 3]>,
+}
+
+impl Ansi {
+    /// Writes the character in the colors, keeping the previous foreground color
+    /// if none is given
+    fn push(&mut self, foreground: Option<[u8; 3]>, background: Option<[u8; 3]>, ch: char) {
+        if let Some([r, g, b]) = foreground.filter(|&c| Some(c) != self.foreground) {
+            write!(self.text, "\x1b[38;2;{};{};{}m", r, g, b).unwrap();
+            self.foreground = Some([r, g, b]);
+        }
+        if let Some([r, g, b]) = background.filter(|&c| Some(c) != self.background) {
+            write!(self.text, "\x1b[48;2;{};{};{}m", r, g, b).unwrap();
+            self.background = Some([r, g, b]);
+        }
+        self.text.push(ch);
+    }
+
+    /// Returns the line, resetting the colors at its end
+    fn finish(mut self) -> String {
+        if self.foreground.is_some() || self.background.is_some() {
+            self.text.push_str("\x1b[0m");
+        }
+        self.text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cgol::patterns;
+
+    fn mono(glyphs: Glyphs) -> TextRenderer {
+        TextRenderer { glyphs, color: false, ..TextRenderer::default() }
+    }
+
+    #[test]
+    fn half_block() {
+        let renderer = mono(Glyphs::HalfBlock);
+        assert_eq!(renderer.draw(&patterns::GLIDER, [0, 0, 3, 3], |_, _| false), [" ▀▄", "▀▀▀"]);
+        assert_eq!(renderer.text_size([3, 3]), [3, 2]);
+
+        // Marked cells are inverted, & the region may extend past the grid
+        let marked = renderer.draw(&patterns::BLINKER, [0, 0, 2, 4], |col, row| (col, row) == (1, 1));
+        assert_eq!(marked, ["█▄", "▀ "]);
+    }
+
+    #[test]
+    fn braille() {
+        let renderer = mono(Glyphs::Braille);
+        assert_eq!(renderer.draw(&patterns::GLIDER, [0, 0, 3, 3], |_, _| false), ["⠬⠆"]);
+        assert_eq!(renderer.draw(&patterns::BLOCK_2, [0, 0, 2, 2], |_, _| false), ["⠛"]);
+        assert_eq!(renderer.grid_size([3, 2]), [6, 8]);
+    }
+
+    #[test]
+    fn colors() {
+        let renderer = TextRenderer { show_age: false, ..TextRenderer::default() };
+        let lines = renderer.draw(&patterns::GLIDER, [0, 0, 2, 2], |_, _| false);
+        assert_eq!(lines, ["\x1b[48;2;0;0;0m \x1b[38;2;255;255;255m▀\x1b[0m"]);
+
+        let grid = Grid::from_slice(1, 2, &[CgolCell::Live(1); 2]);
+        let aged = TextRenderer::default().draw(&grid, [0, 0, 1, 2], |_, _| false);
+        assert_eq!(aged, ["\x1b[48;2;128;128;128m \x1b[0m"]);
+    }
+}
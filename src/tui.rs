@@ -0,0 +1,228 @@
+use crate::{config::{self, Config}, paint, saved_brush, session, utils::RangeExt, Brush};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use seagull::{
+    cgol::CgolCell,
+    soup::Soup,
+    render::Palette,
+    terminal::{Glyphs, TextRenderer},
+    Automaton, Cgol,
+};
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+/// Number of lines below the grid showing the controls & status
+const STATUS_LINES: u16 = 3;
+
+/// Puts the terminal into raw mode on an alternate screen for as long as it
+/// lives, so the terminal is restored even after a panic
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let raw = Self;
+        queue!(io::stdout(), EnterAlternateScreen, Hide)?;
+        io::stdout().flush()?;
+        Ok(raw)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = queue!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = io::stdout().flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Runs the automaton in the terminal, with the cursor moved by the arrow keys
+pub fn run(
+    mut cgol: Automaton<Cgol>,
+    settings: session::Settings,
+    config: &Config,
+    brushes: &[(String, Vec<Brush>)],
+    session_path: &str,
+) -> Result<(), String> {
+    let mut renderer = TextRenderer {
+        color: std::env::var_os("NO_COLOR").is_none(),
+        palette: config.theme.1.clone(),
+        ..TextRenderer::default()
+    };
+
+    let themes = config.themes();
+    let mut theme_idx = config::theme_index(&themes, &renderer.palette);
+    let gradients = config.gradients();
+    let mut gradient_idx = gradients.iter().position(|g| *g == renderer.palette.ages).unwrap_or(0);
+
+    let mut running = false;
+    let mut cursor = [cgol.cols() / 2, cgol.rows() / 2];
+    // Top left cell of the part of the board shown
+    let mut view = [0usize; 2];
+    let mut last_update = Instant::now();
+
+    let step_millis_range = 16..=1024;
+    let mut step_millis = settings.step_millis.map_or(64, |millis| step_millis_range.clamp(millis));
+    let (mut category_idx, mut brush_idx) = saved_brush(brushes, &settings).unwrap_or((0, 0));
+    let mut soup_seed = None;
+    let mut session_status = String::new();
+
+    let _raw = RawTerminal::enter().map_err(|e| e.to_string())?;
+    let mut stdout = io::BufWriter::new(io::stdout());
+
+    loop {
+        let (width, height) = terminal::size().map_err(|e| e.to_string())?;
+        let lines = height.saturating_sub(STATUS_LINES) as usize;
+        let [cols, rows] = renderer.grid_size([width as usize, lines]);
+        let [cols, rows] = [cols.min(cgol.cols()), rows.min(cgol.rows())];
+
+        // Scroll just enough to keep the cursor in view
+        for axis in 0..2 {
+            let size = [cols, rows][axis];
+            if cursor[axis] < view[axis] {
+                view[axis] = cursor[axis];
+            } else if cursor[axis] >= view[axis] + size {
+                view[axis] = cursor[axis] + 1 - size;
+            }
+            view[axis] = view[axis].min([cgol.cols(), cgol.rows()][axis] - size);
+        }
+
+        let (category, category_brushes) = &brushes[category_idx];
+        let brush = category_brushes[brush_idx];
+        let [left, top] = [
+            cursor[0] as isize - brush.1.cols() as isize / 2,
+            cursor[1] as isize - brush.1.rows() as isize / 2,
+        ];
+        let under_brush = |col: usize, row: usize| {
+            let (col, row) = (col as isize - left, row as isize - top);
+            (0..brush.1.cols() as isize).contains(&col)
+                && (0..brush.1.rows() as isize).contains(&row)
+                && matches!(brush.1[(col as usize, row as usize)], CgolCell::Live(_))
+        };
+
+        let mut text = renderer.draw(cgol.cells(), [view[0], view[1], cols, rows], under_brush);
+        text.push(format!(
+            "[Space] {} [+/-] step: {}ms [G] category: {} [B] brush: {} [A] age: {:?} [K] ages: {} [T] theme: {} [Y] glyphs: {:?}",
+            if running { "running" } else { "paused" },
+            step_millis,
+            category,
+            brush.0,
+            renderer.show_age,
+            renderer.palette.ages.name(),
+            themes[theme_idx].0,
+            renderer.glyphs,
+        ));
+        text.push(format!(
+            "[Arrows] move (Shift: faster) [Enter] paint [R] randomize [C] clear [Ctrl+S] save [Q] quit  cursor: {},{}",
+            cursor[0], cursor[1],
+        ));
+        text.push(format!(
+            "generation: {} population: {} soup seed: {} session: {} {}",
+            cgol.generation(),
+            cgol.stats().population,
+            soup_seed.map_or("-".to_string(), |s: u64| s.to_string()),
+            session_path,
+            session_status,
+        ));
+
+        // Status lines are cut off rather than wrapped over each other
+        for line in &mut text[rows.div_ceil(renderer.cells_per_char()[1])..] {
+            *line = line.chars().take(width as usize).collect();
+        }
+
+        for (i, line) in text.iter().enumerate() {
+            queue!(stdout, MoveTo(0, i as u16), Print(line), Clear(ClearType::UntilNewLine)).map_err(|e| e.to_string())?;
+        }
+        queue!(stdout, Clear(ClearType::FromCursorDown)).map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+
+        // Waits for input until the next step is due
+        let step = Duration::from_millis(step_millis);
+        let timeout = match running {
+            true => step.saturating_sub(last_update.elapsed()),
+            false => Duration::from_millis(250),
+        };
+
+        if event::poll(timeout).map_err(|e| e.to_string())? {
+            let key = match event::read().map_err(|e| e.to_string())? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                _ => continue,
+            };
+
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let distance = if key.modifiers.contains(KeyModifiers::SHIFT) { 8 } else { 1 };
+
+            match key {
+                KeyEvent { code: KeyCode::Char('q') | KeyCode::Esc, .. } => break,
+                KeyEvent { code: KeyCode::Char('c'), .. } if ctrl => break,
+                KeyEvent { code: KeyCode::Char('s'), .. } if ctrl => {
+                    let settings = session::Settings {
+                        step_millis: Some(step_millis),
+                        category: Some(category.clone()),
+                        brush: Some(brush.0.to_string()),
+                    };
+
+                    session_status = match session::save(session_path, &cgol, &settings) {
+                        Ok(()) => "(saved)".to_string(),
+                        Err(e) => format!("(error: {})", e),
+                    };
+                }
+                KeyEvent { code: KeyCode::Char(' '), .. } => running = !running,
+                KeyEvent { code: KeyCode::Char('c'), .. } => cgol.clear(),
+                KeyEvent { code: KeyCode::Char('a'), .. } => renderer.show_age = !renderer.show_age,
+                KeyEvent { code: KeyCode::Char('k'), .. } => {
+                    gradient_idx = (gradient_idx + 1) % gradients.len();
+                    renderer.palette.ages = gradients[gradient_idx].clone();
+                }
+                KeyEvent { code: KeyCode::Char('t'), .. } => {
+                    theme_idx = (theme_idx + 1) % themes.len();
+                    let ages = std::mem::take(&mut renderer.palette.ages);
+                    renderer.palette = Palette { ages, ..themes[theme_idx].1.clone() };
+                }
+                KeyEvent { code: KeyCode::Char('y'), .. } => {
+                    renderer.glyphs = match renderer.glyphs {
+                        Glyphs::HalfBlock => Glyphs::Braille,
+                        Glyphs::Braille => Glyphs::HalfBlock,
+                    }
+                }
+                KeyEvent { code: KeyCode::Char('+' | '='), .. } => {
+                    step_millis = step_millis_range.clamp(step_millis / 2)
+                }
+                KeyEvent { code: KeyCode::Char('-'), .. } => step_millis = step_millis_range.clamp(step_millis * 2),
+                KeyEvent { code: KeyCode::Char('r'), .. } => {
+                    let soup = Soup::new(rand::random()).size(cgol.cols(), cgol.rows());
+                    soup_seed = Some(soup.seed());
+                    cgol.clear();
+                    cgol.put(&soup.generate(), 0, 0);
+                }
+                KeyEvent { code: KeyCode::Char('g'), .. } => {
+                    category_idx = (category_idx + 1) % brushes.len();
+                    brush_idx = 0;
+                }
+                KeyEvent { code: KeyCode::Char('b'), .. } => brush_idx = (brush_idx + 1) % category_brushes.len(),
+                KeyEvent { code: KeyCode::Enter | KeyCode::Char('x'), .. } => {
+                    paint(&mut cgol, brush.1, category_idx == 0, cursor.map(|n| n as isize))
+                }
+                KeyEvent { code: KeyCode::Left, .. } => cursor[0] = cursor[0].saturating_sub(distance),
+                KeyEvent { code: KeyCode::Right, .. } => cursor[0] = (cursor[0] + distance).min(cgol.cols().saturating_sub(1)),
+                KeyEvent { code: KeyCode::Up, .. } => cursor[1] = cursor[1].saturating_sub(distance),
+                KeyEvent { code: KeyCode::Down, .. } => cursor[1] = (cursor[1] + distance).min(cgol.rows().saturating_sub(1)),
+                _ => (),
+            }
+        }
+
+        if running && last_update.elapsed() >= Duration::from_millis(step_millis) {
+            last_update = Instant::now();
+            cgol.step();
+        }
+    }
+
+    Ok(())
+}